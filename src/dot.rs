@@ -0,0 +1,216 @@
+use std::{fmt, io, process::Stdio, time::Duration};
+
+use futures::{AsyncReadExt, AsyncWriteExt};
+use smol::{future::FutureExt, process::Command, Timer};
+
+/// Graphviz layout engines, each of them being a binary of its own.
+#[derive(Clone, Copy, Debug, Hash, PartialEq)]
+pub enum GraphEngine {
+    Dot,
+    Neato,
+    Fdp,
+    Sfdp,
+    Circo,
+    Twopi,
+}
+
+impl GraphEngine {
+    pub const ALL: [GraphEngine; 6] = [
+        GraphEngine::Dot,
+        GraphEngine::Neato,
+        GraphEngine::Fdp,
+        GraphEngine::Sfdp,
+        GraphEngine::Circo,
+        GraphEngine::Twopi,
+    ];
+
+    pub fn command(self) -> &'static str {
+        match self {
+            GraphEngine::Dot => "dot",
+            GraphEngine::Neato => "neato",
+            GraphEngine::Fdp => "fdp",
+            GraphEngine::Sfdp => "sfdp",
+            GraphEngine::Circo => "circo",
+            GraphEngine::Twopi => "twopi",
+        }
+    }
+
+    pub fn from_command(command: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|engine| engine.command() == command)
+    }
+}
+
+impl fmt::Display for GraphEngine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.command())
+    }
+}
+
+#[derive(Debug)]
+pub enum DotError {
+    /// The Graphviz binary of the engine could not be found in `PATH`.
+    NotInstalled(GraphEngine),
+    /// The engine could not be run, or its input and output could not be piped.
+    Io(GraphEngine, io::Error),
+    /// The engine ran but exited with an error, the message being what it wrote to stderr.
+    Failed(GraphEngine, String),
+    /// The layout took longer than allowed, the engine was killed then.
+    TimedOut(GraphEngine, Duration),
+}
+
+impl fmt::Display for DotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DotError::NotInstalled(engine) => write!(f, "Graphviz `{engine}` not found in PATH"),
+            DotError::Io(engine, err) => write!(f, "Could not run Graphviz `{engine}`: {err}"),
+            DotError::Failed(engine, error_message) => {
+                write!(f, "Graphviz `{engine}` failed: {}", error_message.trim())
+            }
+            DotError::TimedOut(engine, timeout) => write!(
+                f,
+                "Graph layout timed out, `{engine}` ran for more than {}s",
+                timeout.as_secs_f64()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DotError::Io(_, err) => Some(err),
+            DotError::NotInstalled(_) | DotError::Failed(..) | DotError::TimedOut(..) => None,
+        }
+    }
+}
+
+const DOT_ATTEMPTS: usize = 3;
+
+const DOT_RETRY_DELAY_MS: u64 = 200;
+
+/// Renders the graph as SVG, running the engine again if it fails once in a while, e.g. because of
+/// resource limits, but not if it is not installed or if the layout timed out, which it would do
+/// again.
+pub async fn dot_to_svg(
+    engine: GraphEngine,
+    dot_source: &str,
+    timeout: Duration,
+) -> Result<String, DotError> {
+    let mut attempt = 1;
+    let output = loop {
+        match run_dot(engine, "svg", dot_source, timeout).await {
+            Ok(output) => break output,
+            Err(err @ (DotError::NotInstalled(_) | DotError::TimedOut(..))) => return Err(err),
+            Err(err) if attempt < DOT_ATTEMPTS => {
+                eprintln!("Could not run {engine} (attempt {attempt}): {err}");
+                attempt += 1;
+                Timer::after(Duration::from_millis(DOT_RETRY_DELAY_MS)).await;
+            }
+            Err(err) => return Err(err),
+        }
+    };
+    Ok(String::from_utf8_lossy(&output).to_string())
+}
+
+/// Version of Graphviz, as told by `dot -V`, e.g. `2.43.0 (0)`.
+pub async fn graphviz_version() -> Result<String, DotError> {
+    let engine = GraphEngine::Dot;
+    let output = Command::new(engine.command())
+        .arg("-V")
+        .output()
+        .await
+        .map_err(|err| command_error(engine, err))?;
+
+    // The version is written to stderr, e.g. `dot - graphviz version 2.43.0 (0)`.
+    let message = String::from_utf8_lossy(&output.stderr);
+    if output.status.success() {
+        Ok(message
+            .split_once("version ")
+            .map_or(&*message, |(_, version)| version)
+            .trim()
+            .to_owned())
+    } else {
+        Err(DotError::Failed(engine, message.to_string()))
+    }
+}
+
+pub async fn dot_to_png(
+    engine: GraphEngine,
+    dot_source: &str,
+    timeout: Duration,
+) -> Result<Vec<u8>, DotError> {
+    run_dot(engine, "png", dot_source, timeout).await
+}
+
+async fn run_dot(
+    engine: GraphEngine,
+    format: &str,
+    dot_source: &str,
+    timeout: Duration,
+) -> Result<Vec<u8>, DotError> {
+    let mut child = Command::new(engine.command())
+        .arg(format!("-T{format}"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // In case the future is dropped before the engine exits.
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| command_error(engine, err))?;
+
+    let stdin = child.stdin.take();
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let run = async {
+        // Written while the output is read, the engine may start writing before reading it all.
+        let write = async {
+            if let Some(mut stdin) = stdin {
+                stdin.write_all(dot_source.as_bytes()).await?;
+            }
+            io::Result::Ok(())
+        };
+        let (_, stdout, stderr) = futures::try_join!(write, read_all(stdout), read_all(stderr))?;
+        let status = child.status().await?;
+        Ok::<_, io::Error>(Some((status, stdout, stderr)))
+    }
+    .or(async {
+        Timer::after(timeout).await;
+        Ok(None)
+    })
+    .await
+    .map_err(|err| DotError::Io(engine, err))?;
+
+    let Some((status, stdout, stderr)) = run else {
+        // Waited for, so that no zombie process is left behind.
+        child.kill().map_err(|err| DotError::Io(engine, err))?;
+        child
+            .status()
+            .await
+            .map_err(|err| DotError::Io(engine, err))?;
+        return Err(DotError::TimedOut(engine, timeout));
+    };
+
+    if status.success() {
+        Ok(stdout)
+    } else {
+        let error_message = String::from_utf8_lossy(&stderr);
+        Err(DotError::Failed(engine, error_message.to_string()))
+    }
+}
+
+async fn read_all(pipe: Option<impl futures::AsyncRead + Unpin>) -> io::Result<Vec<u8>> {
+    let mut output = Vec::new();
+    if let Some(mut pipe) = pipe {
+        pipe.read_to_end(&mut output).await?;
+    }
+    Ok(output)
+}
+
+fn command_error(engine: GraphEngine, err: io::Error) -> DotError {
+    match err.kind() {
+        io::ErrorKind::NotFound => DotError::NotInstalled(engine),
+        _ => DotError::Io(engine, err),
+    }
+}
@@ -0,0 +1,1595 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    fmt::Write,
+    hash::{DefaultHasher, Hash, Hasher},
+    time::{Duration, Instant},
+};
+
+use crate::dot::GraphEngine;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RankDir {
+    TopToBottom,
+    LeftToRight,
+}
+
+impl RankDir {
+    pub const ALL: [RankDir; 2] = [RankDir::TopToBottom, RankDir::LeftToRight];
+
+    pub fn attr(self) -> &'static str {
+        match self {
+            RankDir::TopToBottom => "TB",
+            RankDir::LeftToRight => "LR",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RankDir::TopToBottom => "↓",
+            RankDir::LeftToRight => "→",
+        }
+    }
+}
+
+/// Style of the generated graph, Graphviz defaults applying to what is unset.
+#[derive(Clone, Default, PartialEq, Hash)]
+pub struct DotStyle {
+    /// Ignored if blank.
+    pub font_name: String,
+    pub font_size: Option<u32>,
+    pub node_shape: Option<NodeShape>,
+}
+
+impl DotStyle {
+    fn font_attrs(&self) -> Vec<(&'static str, String)> {
+        let font_name = self.font_name.trim();
+        (!font_name.is_empty())
+            .then(|| ("fontname", dot_escape(font_name)))
+            .into_iter()
+            .chain(self.font_size.map(|size| ("fontsize", size.to_string())))
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Hash)]
+pub enum NodeShape {
+    Box,
+    Ellipse,
+    Record,
+}
+
+impl NodeShape {
+    pub const ALL: [NodeShape; 3] = [NodeShape::Box, NodeShape::Ellipse, NodeShape::Record];
+
+    pub fn attr(self) -> &'static str {
+        match self {
+            NodeShape::Box => "box",
+            NodeShape::Ellipse => "ellipse",
+            NodeShape::Record => "record",
+        }
+    }
+
+    pub fn from_attr(attr: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|shape| shape.attr() == attr)
+    }
+}
+
+/// Edges are colored according to the number of in-flight records, i.e. the records written by the
+/// tail node but not yet read by the head node.
+#[derive(Clone, Copy, PartialEq, Hash)]
+pub struct EdgeThresholds {
+    /// From that many in-flight records, the edge is considered busy.
+    pub busy: i32,
+    /// From that many in-flight records, the edge is considered backed up.
+    pub backed_up: i32,
+}
+
+impl Default for EdgeThresholds {
+    fn default() -> Self {
+        Self {
+            busy: 10,
+            backed_up: 42,
+        }
+    }
+}
+
+/// Labels at both ends of an edge, as templates whose placeholders are replaced by the counters of
+/// the edge, see `fill_label_template`.
+#[derive(Clone, PartialEq, Hash)]
+pub struct EdgeLabelTemplates {
+    pub tail: String,
+    pub head: String,
+}
+
+impl Default for EdgeLabelTemplates {
+    fn default() -> Self {
+        Self {
+            tail: "{tail}".to_owned(),
+            // The difference is unknown while the tail node has no status.
+            head: "{head}[ ({diff})]".to_owned(),
+        }
+    }
+}
+
+impl EdgeLabelTemplates {
+    /// Tells what is wrong with the first invalid template, if any.
+    pub fn validate(&self) -> Result<(), String> {
+        let values = LABEL_PLACEHOLDERS.map(|name| (name, None));
+        fill_label_template(&self.tail, &values)?;
+        fill_label_template(&self.head, &values)?;
+        Ok(())
+    }
+}
+
+/// Records written by the tail node, read by the head node, and in flight in between.
+const LABEL_PLACEHOLDERS: [&str; 3] = ["tail", "head", "diff"];
+
+/// Replaces every `{name}` placeholder of `template` by its value, unknown values being shown as
+/// `?`, unless they are within an optional `[...]` segment which is then left out altogether.
+fn fill_label_template(
+    template: &str,
+    values: &[(&str, Option<String>)],
+) -> Result<String, String> {
+    let mut label = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find(['[', ']']) {
+        label.push_str(&fill_label_segment(&rest[..start], values)?.0);
+        if rest[start..].starts_with(']') {
+            return Err("unmatched `]`".to_owned());
+        }
+        let end = start + rest[start..].find(']').ok_or("unmatched `[`")?;
+        let optional = &rest[start + 1..end];
+        if optional.contains('[') {
+            return Err("nested `[`".to_owned());
+        }
+        let (segment, complete) = fill_label_segment(optional, values)?;
+        if complete {
+            label.push_str(&segment);
+        }
+        rest = &rest[end + 1..];
+    }
+    label.push_str(&fill_label_segment(rest, values)?.0);
+    Ok(label)
+}
+
+/// Fills the placeholders of a segment without optional parts, telling whether all their values
+/// are known.
+fn fill_label_segment(
+    template: &str,
+    values: &[(&str, Option<String>)],
+) -> Result<(String, bool), String> {
+    let mut label = String::new();
+    let mut complete = true;
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        label.push_str(&rest[..start]);
+        if rest[start..].starts_with('}') {
+            return Err("unmatched `}`".to_owned());
+        }
+        let end = start + rest[start..].find('}').ok_or("unmatched `{`")?;
+        let name = &rest[start + 1..end];
+        let (_, value) = values
+            .iter()
+            .find(|(placeholder, _)| *placeholder == name)
+            .ok_or_else(|| format!("unknown placeholder `{{{name}}}`"))?;
+        complete &= value.is_some();
+        label.push_str(value.as_deref().unwrap_or("?"));
+        rest = &rest[end + 1..];
+    }
+    label.push_str(rest);
+    Ok((label, complete))
+}
+
+#[derive(Clone, PartialEq, Hash)]
+pub enum NodeState {
+    Waiting,
+    Running,
+    Success,
+    Error(String),
+    /// No status was reported for the node.
+    Unknown,
+}
+
+impl NodeState {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NodeState::Waiting => "Waiting",
+            NodeState::Running => "Running",
+            NodeState::Success => "Success",
+            NodeState::Error(_) => "Error",
+            NodeState::Unknown => "Unknown",
+        }
+    }
+
+    pub fn color(&self, palette: &Palette) -> &'static str {
+        match self {
+            NodeState::Waiting | NodeState::Unknown => palette.neutral,
+            NodeState::Running => palette.busy,
+            NodeState::Success => palette.good,
+            NodeState::Error(_) => palette.bad,
+        }
+    }
+
+    /// Border of the node as a DOT attribute, when states are told apart by their borders.
+    fn dot_border(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            NodeState::Waiting => Some(("style", "dotted")),
+            NodeState::Running => Some(("penwidth", "3")),
+            NodeState::Error(_) => Some(("peripheries", "2")),
+            NodeState::Success | NodeState::Unknown => None,
+        }
+    }
+
+    /// Same as `dot_border`, as a CSS border style.
+    pub fn css_border(&self) -> Option<&'static str> {
+        match self {
+            NodeState::Waiting => Some("dotted"),
+            NodeState::Running => Some("solid"),
+            NodeState::Error(_) => Some("double"),
+            NodeState::Success | NodeState::Unknown => None,
+        }
+    }
+
+    /// How much attention the state deserves, a collapsed cluster taking the state of its worst
+    /// member.
+    pub fn severity(&self) -> u8 {
+        match self {
+            NodeState::Success => 0,
+            NodeState::Running => 1,
+            NodeState::Waiting => 2,
+            NodeState::Unknown => 3,
+            NodeState::Error(_) => 4,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Hash)]
+pub struct NodeDetails {
+    pub state: NodeState,
+    pub input_read: Vec<u64>,
+    pub output_written: Vec<u64>,
+}
+
+pub fn node_name_to_dot_id(name: &str) -> String {
+    format!("\"{name}\"")
+}
+
+fn format_rate(records_per_second: f64) -> String {
+    if records_per_second < 10.0 {
+        format!("{records_per_second:.1}")
+    } else {
+        format!("{records_per_second:.0}")
+    }
+}
+
+/// Replaces the transparent background of the graph, if a color is given.
+pub fn with_background(dot: &str, color: Option<&str>) -> String {
+    match color {
+        Some(color) => dot.replacen(
+            "bgcolor=\"transparent\"",
+            &format!("bgcolor=\"{}\"", dot_escape(color)),
+            1,
+        ),
+        None => dot.to_owned(),
+    }
+}
+
+/// Formats an edge counter, or the records in flight across the edge, as a percentage of the
+/// expected records, if any.
+fn format_counter(records: i64, expected_records: Option<u64>) -> String {
+    match expected_records {
+        Some(expected) => format!("{:.0}%", records as f64 * 100.0 / expected as f64),
+        None => records.to_string(),
+    }
+}
+
+/// Escapes `text` so that it can be used within a DOT HTML-like label.
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escapes `text` so that it can be used as a quoted DOT attribute value.
+pub fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Colors of the graph elements, depending on the background they are drawn on.
+#[derive(Clone, Copy, PartialEq, Hash)]
+pub struct Palette {
+    /// Color of the text and of anything not colored by its state.
+    pub foreground: &'static str,
+    /// Waiting or unknown nodes.
+    pub neutral: &'static str,
+    /// Successful nodes and flowing edges.
+    pub good: &'static str,
+    /// Running nodes and busy edges.
+    pub busy: &'static str,
+    /// Failed nodes and backed up edges.
+    pub bad: &'static str,
+    /// Whether node states are also told apart by their borders, so that they can be distinguished
+    /// even when their colors cannot.
+    pub distinct_borders: bool,
+}
+
+impl Palette {
+    /// Color of an edge according to its in-flight records.
+    fn edge_color(&self, in_flight: i32, thresholds: EdgeThresholds) -> &'static str {
+        if in_flight < thresholds.busy {
+            self.good
+        } else if in_flight < thresholds.backed_up {
+            self.busy
+        } else {
+            self.bad
+        }
+    }
+
+    /// Fill color of a running node, from nearly transparent to the solid busy color as `ratio` goes
+    /// from 0 to 1.
+    fn throughput_fill_color(&self, ratio: f64) -> String {
+        let alpha = 0x20 + (ratio.clamp(0.0, 1.0) * f64::from(0xdf)) as u8;
+        format!("{}{alpha:02x}", self.busy)
+    }
+}
+
+pub const LIGHT_PALETTE: Palette = Palette {
+    foreground: "black",
+    neutral: "#59636e",
+    good: "#1a7f37",
+    busy: "#dbab0a",
+    bad: "#d1242f",
+    distinct_borders: false,
+};
+
+pub const DARK_PALETTE: Palette = Palette {
+    foreground: "white",
+    neutral: "#9198a1",
+    good: "#3fb950",
+    busy: "#e3b341",
+    bad: "#f85149",
+    distinct_borders: false,
+};
+
+/// Blue, yellow and purple rather than green, orange and red, which are hard to tell apart with the
+/// most common color vision deficiencies.
+pub const LIGHT_COLOR_BLIND_PALETTE: Palette = Palette {
+    foreground: "black",
+    neutral: "#59636e",
+    good: "#0072b2",
+    busy: "#e69f00",
+    bad: "#8e44ad",
+    distinct_borders: true,
+};
+
+pub const DARK_COLOR_BLIND_PALETTE: Palette = Palette {
+    foreground: "white",
+    neutral: "#9198a1",
+    good: "#56b4e9",
+    busy: "#f0e442",
+    bad: "#cc79a7",
+    distinct_borders: true,
+};
+
+pub const DEFAULT_CLUSTER_DELIMITER: &str = "::";
+
+const ACTIVE_EDGE_PENWIDTH: &str = "2.5";
+
+const CRITICAL_PATH_PENWIDTH: &str = "4";
+
+/// Class of the SVG elements of the edges which moved records since the previous poll.
+const ACTIVE_EDGE_CLASS: &str = "active";
+
+/// Number of polls kept in the state history of every node.
+const NODE_HISTORY_LENGTH: usize = 20;
+
+/// Returns the cluster of the node, i.e. its name up to the first delimiter.
+pub fn node_cluster<'a>(node_name: &'a str, delimiter: &str) -> Option<&'a str> {
+    if delimiter.is_empty() {
+        return None;
+    }
+    node_name.split_once(delimiter).map(|(cluster, _)| cluster)
+}
+
+/// Prefix of the names of the nodes added by the console, which pipelines must not use.
+const CONSOLE_NODE_PREFIX: &str = "__qbc_";
+
+/// Name of the summary node standing for a collapsed cluster, which cannot clash with the name of
+/// a node of the pipeline.
+fn collapsed_node_name(cluster: &str) -> String {
+    format!("{CONSOLE_NODE_PREFIX}cluster:{cluster}")
+}
+
+/// Name of the node holding the note of a node, which cannot clash with the name of a node of the
+/// pipeline either.
+fn note_node_name(node_name: &str) -> String {
+    format!("{CONSOLE_NODE_PREFIX}note:{node_name}")
+}
+
+/// Returns the name of the node as rendered, which is the summary node of its cluster if collapsed.
+fn displayed_node_name<'a>(
+    node_name: &'a str,
+    delimiter: &str,
+    collapsed: &BTreeSet<String>,
+) -> Cow<'a, str> {
+    match node_cluster(node_name, delimiter) {
+        Some(cluster) if collapsed.contains(cluster) => Cow::Owned(collapsed_node_name(cluster)),
+        _ => Cow::Borrowed(node_name),
+    }
+}
+
+/// Nodes and edges of a pipeline, which do not change while it runs.
+pub struct PipelineGraph {
+    /// Sorted by name.
+    pub nodes: Vec<String>,
+    /// Tail node and output index, head node and input index, sorted.
+    pub edges: Vec<(String, usize, String, usize)>,
+}
+
+/// Everything the generated DOT source depends on besides the pipeline itself.
+pub struct GraphOptions {
+    pub palette: &'static Palette,
+    pub rank_dir: RankDir,
+    pub dot_style: DotStyle,
+    pub edge_thresholds: EdgeThresholds,
+    pub label_templates: EdgeLabelTemplates,
+    pub cluster_delimiter: String,
+    pub collapsed_clusters: BTreeSet<String>,
+    pub show_counters: bool,
+    /// Set if the edge counters are shown as a percentage of it.
+    pub expected_records: Option<u64>,
+    pub critical_path: bool,
+    pub mark: Option<BTreeMap<String, NodeDetails>>,
+    pub notes: BTreeMap<String, String>,
+    /// Node to show with its direct neighbors only.
+    pub focus: Option<String>,
+}
+
+impl Default for GraphOptions {
+    fn default() -> Self {
+        Self {
+            palette: &LIGHT_PALETTE,
+            rank_dir: RankDir::TopToBottom,
+            dot_style: DotStyle::default(),
+            edge_thresholds: EdgeThresholds::default(),
+            label_templates: EdgeLabelTemplates::default(),
+            cluster_delimiter: DEFAULT_CLUSTER_DELIMITER.to_owned(),
+            collapsed_clusters: BTreeSet::new(),
+            show_counters: true,
+            expected_records: None,
+            critical_path: false,
+            mark: None,
+            notes: BTreeMap::new(),
+            focus: None,
+        }
+    }
+}
+
+pub struct GraphFrame {
+    pub dot: String,
+    pub nodes: BTreeMap<String, NodeDetails>,
+    /// Set once all the nodes are done, successfully or not.
+    pub finished: bool,
+    /// Nodes which were not failed as of the previous poll.
+    pub failed_nodes: Vec<String>,
+    /// Whether a shown edge has at least `EdgeThresholds::backed_up` records in flight.
+    pub backed_up: bool,
+}
+
+/// Generates the DOT source of the pipeline from the statuses of its nodes.
+///
+/// `history` and `previous_head_counters` carry what the graph depends on from one poll to the
+/// next, they are updated with the given statuses.
+pub fn build_dot(
+    graph: &PipelineGraph,
+    statuses: &BTreeMap<String, NodeDetails>,
+    options: &GraphOptions,
+    history: &mut BTreeMap<String, VecDeque<NodeState>>,
+    previous_head_counters: &mut Option<(Instant, Vec<Option<u64>>)>,
+) -> Result<GraphFrame, std::fmt::Error> {
+    let mut dot = String::new();
+
+    writeln!(&mut dot, "digraph G {{")?;
+
+    writeln!(
+        &mut dot,
+        "    graph [bgcolor=\"transparent\", rankdir=\"{}\"];",
+        options.rank_dir.attr()
+    )?;
+
+    let palette = options.palette;
+    for element in ["node", "edge"] {
+        writeln!(
+            &mut dot,
+            "    {element} [fontcolor=\"{0}\", color=\"{0}\"];",
+            palette.foreground
+        )?;
+    }
+
+    let style = &options.dot_style;
+    let font_attrs = style.font_attrs();
+    let node_attrs = font_attrs
+        .iter()
+        .cloned()
+        .chain(
+            style
+                .node_shape
+                .map(|shape| ("shape", shape.attr().to_owned())),
+        )
+        .collect::<Vec<_>>();
+    for (element, attrs) in [("node", node_attrs), ("edge", font_attrs)] {
+        if !attrs.is_empty() {
+            writeln!(
+                &mut dot,
+                "    {element} [{}];",
+                attrs
+                    .iter()
+                    .map(|(attr, val)| format!("{attr}=\"{val}\""))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+    }
+
+    // A process may register its state before its pipeline, which is not finished then.
+    let mut finished = !graph.nodes.is_empty();
+    let mut nodes_details = BTreeMap::new();
+    let thresholds = options.edge_thresholds;
+
+    let nodes = graph
+        .nodes
+        .iter()
+        .map(|node_name| {
+            // The statuses are fetched separately from the graph and may miss a node, which
+            // most likely gets sorted out by the next poll.
+            let details = statuses
+                .get(node_name)
+                .cloned()
+                .unwrap_or_else(|| NodeDetails {
+                    state: NodeState::Unknown,
+                    input_read: Vec::new(),
+                    output_written: Vec::new(),
+                });
+            let read_records = details
+                .input_read
+                .iter()
+                .copied()
+                .reduce(|acc, read| acc + read);
+            let written_records = details
+                .output_written
+                .iter()
+                .copied()
+                .reduce(|acc, written| acc + written);
+            let total_records = read_records.map_or(written_records, |read| {
+                Some(read + written_records.unwrap_or(0))
+            });
+            (
+                node_name.as_str(),
+                details,
+                read_records,
+                written_records,
+                total_records,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Running nodes are shaded according to the records they processed relative to the busiest
+    // one, which makes hotspots stand out.
+    let max_running_records = nodes
+        .iter()
+        .filter(|(_, details, _, _, _)| details.state == NodeState::Running)
+        .filter_map(|(_, _, _, _, total_records)| *total_records)
+        .max()
+        .unwrap_or(0);
+
+    let delimiter = &options.cluster_delimiter;
+    let collapsed = &options.collapsed_clusters;
+    // Statements of the nodes of each expanded cluster.
+    let mut cluster_dots = BTreeMap::<&str, String>::new();
+    // Number of nodes and worst state of each collapsed cluster.
+    let mut collapsed_summaries = BTreeMap::<&str, (usize, NodeState)>::new();
+    let marked = &options.mark;
+    let mut failed_nodes = Vec::new();
+    // The focused node along with its predecessors and successors.
+    let focused = options.focus.as_ref().map(|focus| {
+        graph
+            .edges
+            .iter()
+            .filter_map(|(tail_name, _, head_name, _)| {
+                if head_name == focus {
+                    Some(tail_name.as_str())
+                } else if tail_name == focus {
+                    Some(head_name.as_str())
+                } else {
+                    None
+                }
+            })
+            .chain([focus.as_str()])
+            .collect::<BTreeSet<_>>()
+    });
+    let is_shown = |node_name: &str| {
+        focused
+            .as_ref()
+            .is_none_or(|focused| focused.contains(node_name))
+    };
+    let critical_edges = if options.critical_path {
+        critical_path(graph)
+    } else {
+        BTreeSet::new()
+    };
+    let critical_nodes = critical_edges
+        .iter()
+        .flat_map(|&edge_index| {
+            let (tail_name, _, head_name, _) = &graph.edges[edge_index];
+            [tail_name.as_str(), head_name.as_str()]
+        })
+        .collect::<BTreeSet<_>>();
+
+    for (node_name, details, read_records, written_records, total_records) in nodes {
+        if matches!(
+            details.state,
+            NodeState::Waiting | NodeState::Running | NodeState::Unknown
+        ) {
+            finished = false;
+        }
+
+        let node_history = history.entry(node_name.to_owned()).or_default();
+        // Nodes already failed when connecting are not reported.
+        if matches!(details.state, NodeState::Error(_))
+            && node_history
+                .back()
+                .is_some_and(|state| !matches!(state, NodeState::Error(_)))
+        {
+            failed_nodes.push(node_name.to_owned());
+        }
+        node_history.push_back(details.state.clone());
+        if node_history.len() > NODE_HISTORY_LENGTH {
+            node_history.pop_front();
+        }
+
+        if !is_shown(node_name) {
+            nodes_details.insert(node_name.to_owned(), details);
+            continue;
+        }
+
+        let cluster = node_cluster(node_name, delimiter);
+        if let Some(cluster) = cluster.filter(|cluster| collapsed.contains(*cluster)) {
+            let (count, worst) = collapsed_summaries
+                .entry(cluster)
+                .or_insert((0, NodeState::Success));
+            *count += 1;
+            if details.state.severity() > worst.severity() {
+                *worst = details.state.clone();
+            }
+            nodes_details.insert(node_name.to_owned(), details);
+            continue;
+        }
+        let out = match cluster {
+            Some(cluster) => cluster_dots.entry(cluster).or_default(),
+            None => &mut dot,
+        };
+
+        write!(out, "{} [", node_name_to_dot_id(node_name))?;
+
+        let color = details.state.color(palette);
+
+        let mut tooltip = read_records
+            .map(|read| format!("read: {read}"))
+            .into_iter()
+            .chain(written_records.map(|written| format!("written: {written}")))
+            .collect::<Vec<_>>()
+            .join(", ");
+        // Sums hide imbalances between the ports of a node.
+        if details.input_read.len() > 1 {
+            for (index, read) in details.input_read.iter().enumerate() {
+                write!(&mut tooltip, "\nin[{index}]: {read}")?;
+            }
+        }
+        if details.output_written.len() > 1 {
+            for (index, written) in details.output_written.iter().enumerate() {
+                write!(&mut tooltip, "\nout[{index}]: {written}")?;
+            }
+        }
+        // Counters below the mark mean that the process restarted, there is no difference then.
+        if let Some(marked) = marked.as_ref().and_then(|marked| marked.get(node_name)) {
+            let marked_read = marked.input_read.iter().sum::<u64>();
+            let marked_written = marked.output_written.iter().sum::<u64>();
+            let since_mark = read_records
+                .and_then(|read| read.checked_sub(marked_read))
+                .map(|read| format!("read +{read}"))
+                .into_iter()
+                .chain(
+                    written_records
+                        .and_then(|written| written.checked_sub(marked_written))
+                        .map(|written| format!("written +{written}")),
+                )
+                .collect::<Vec<_>>();
+            if !since_mark.is_empty() {
+                write!(&mut tooltip, "\nsince mark: {}", since_mark.join(", "))?;
+            }
+        }
+        if let NodeState::Error(error) = &details.state {
+            if !tooltip.is_empty() {
+                tooltip.push('\n');
+            }
+            tooltip.push_str(error);
+        }
+
+        let mut attrs = vec![("color", color.to_owned())];
+        if let (NodeState::Running, Some(records)) = (&details.state, total_records) {
+            if max_running_records > 0 {
+                attrs.push(("style", "filled".to_owned()));
+                attrs.push((
+                    "fillcolor",
+                    palette.throughput_fill_color(records as f64 / max_running_records as f64),
+                ));
+            }
+        }
+        // Running nodes which did not process any record yet are told apart from the busy ones.
+        if details.state == NodeState::Unknown
+            || (details.state == NodeState::Running && total_records.is_none())
+        {
+            attrs.push(("style", "dashed".to_owned()));
+        }
+        if palette.distinct_borders {
+            if let Some((attr, val)) = details.state.dot_border() {
+                attrs.push((attr, val.to_owned()));
+            }
+        }
+        if critical_nodes.contains(node_name) {
+            attrs.push(("penwidth", CRITICAL_PATH_PENWIDTH.to_owned()));
+        }
+        if !tooltip.is_empty() {
+            attrs.push(("tooltip", dot_escape(&tooltip)));
+        }
+
+        nodes_details.insert(node_name.to_owned(), details);
+
+        for (i, (attr, val)) in attrs.into_iter().enumerate() {
+            if i > 0 {
+                write!(out, ", ")?;
+            } else {
+                writeln!(out)?;
+            }
+            writeln!(out, "{attr} = \"{val}\"",)?;
+        }
+
+        // HTML-like labels are not quoted, the attributes above always include the color.
+        let node_history = &history[node_name];
+        write!(
+            out,
+            ", label = <<TABLE BORDER=\"0\" CELLSPACING=\"1\" CELLPADDING=\"0\">\
+                <TR><TD COLSPAN=\"{}\">{}</TD></TR><TR>",
+            node_history.len(),
+            html_escape(node_name)
+        )?;
+        for state in node_history {
+            write!(
+                out,
+                "<TD BGCOLOR=\"{}\" WIDTH=\"4\" HEIGHT=\"3\"></TD>",
+                state.color(palette)
+            )?;
+        }
+        writeln!(out, "</TR></TABLE>>")?;
+
+        writeln!(out, "]")?;
+    }
+
+    for (cluster, cluster_dot) in cluster_dots {
+        writeln!(
+            &mut dot,
+            "subgraph {} {{",
+            node_name_to_dot_id(&format!("cluster_{cluster}"))
+        )?;
+        writeln!(&mut dot, "label = \"{}\";", dot_escape(cluster))?;
+        dot.push_str(&cluster_dot);
+        writeln!(&mut dot, "}}")?;
+    }
+
+    for (cluster, (count, worst)) in collapsed_summaries {
+        write!(
+            &mut dot,
+            "{} [",
+            node_name_to_dot_id(&collapsed_node_name(cluster))
+        )?;
+
+        let color = worst.color(palette);
+
+        for (i, (attr, val)) in [
+            ("shape", "box3d".to_owned()),
+            ("label", dot_escape(&format!("{cluster} ({count})"))),
+            ("color", color.to_owned()),
+            (
+                "tooltip",
+                format!("{count} nodes, worst state: {}", worst.label()),
+            ),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            if i > 0 {
+                write!(&mut dot, ", ")?;
+            } else {
+                writeln!(&mut dot)?;
+            }
+            writeln!(&mut dot, "{attr} = \"{val}\"",)?;
+        }
+
+        writeln!(&mut dot, "]")?;
+    }
+
+    // Notes are nodes of their own so that they follow their node whatever the layout, they are
+    // pinned to the summary node of a collapsed cluster.
+    for (node_name, note) in &options.notes {
+        if !nodes_details.contains_key(node_name) || !is_shown(node_name) {
+            continue;
+        }
+        let note_id = node_name_to_dot_id(&note_node_name(node_name));
+        writeln!(
+            &mut dot,
+            "{note_id} [shape = \"note\", style = \"dashed\", label = \"{}\"]",
+            dot_escape(note)
+        )?;
+        writeln!(
+            &mut dot,
+            "{note_id} -> {} [style = \"dotted\", arrowhead = \"none\"]",
+            node_name_to_dot_id(&displayed_node_name(node_name, delimiter, collapsed))
+        )?;
+    }
+
+    let show_counters = options.show_counters;
+    let expected_records = options.expected_records;
+    // Invalid templates are reported in the settings, the default labels are shown meanwhile.
+    let default_templates = EdgeLabelTemplates::default();
+    let templates = match options.label_templates.validate() {
+        Ok(()) => &options.label_templates,
+        Err(_) => &default_templates,
+    };
+
+    let now = Instant::now();
+    let mut head_counters = Vec::with_capacity(graph.edges.len());
+    let mut backed_up = false;
+
+    for (edge_index, (tail_name, tail_index, head_name, head_index)) in
+        graph.edges.iter().enumerate()
+    {
+        // The schema has no port names, the port of a counter is only told by its index when
+        // the node has several.
+        let tail_status = statuses.get(tail_name);
+        let tail_port = match tail_status {
+            Some(s) if s.output_written.len() > 1 => format!("out[{tail_index}] "),
+            _ => String::new(),
+        };
+        let tail_counter = tail_status.and_then(|s| s.output_written.get(*tail_index).copied());
+
+        let head_status = statuses.get(head_name);
+        let head_port = match head_status {
+            Some(s) if s.input_read.len() > 1 => format!("in[{head_index}] "),
+            _ => String::new(),
+        };
+        let head_counter = head_status.and_then(|s| s.input_read.get(*head_index).copied());
+
+        let diff_counter = tail_counter.and_then(|t| head_counter.map(|h| t as i32 - h as i32));
+
+        head_counters.push(head_counter);
+        // Counters going backwards mean that the process restarted, there is no meaningful rate
+        // then.
+        let rate = previous_head_counters
+            .as_ref()
+            .and_then(|(previous_at, previous_counters)| {
+                let previous = previous_counters.get(edge_index).copied().flatten()?;
+                let current = head_counter?;
+                let elapsed = now.duration_since(*previous_at).as_secs_f64();
+                (current >= previous && elapsed > 0.0)
+                    .then(|| (current - previous) as f64 / elapsed)
+            });
+        let since_mark = marked
+            .as_ref()
+            .and_then(|marked| marked.get(head_name)?.input_read.get(*head_index))
+            .and_then(|marked| head_counter?.checked_sub(*marked));
+        let label = rate
+            .map(|r| format!("{}/s", format_rate(r)))
+            .into_iter()
+            .chain(since_mark.map(|n| format!("+{n} since mark")))
+            .collect::<Vec<_>>();
+
+        let tail_node = displayed_node_name(tail_name, delimiter, collapsed);
+        let head_node = displayed_node_name(head_name, delimiter, collapsed);
+        // Edges within a collapsed cluster are hidden along with its nodes.
+        if (tail_node == head_node && tail_name != head_name)
+            || !is_shown(tail_name)
+            || !is_shown(head_name)
+        {
+            continue;
+        }
+        backed_up |= diff_counter.is_some_and(|d| d >= thresholds.backed_up);
+
+        write!(
+            &mut dot,
+            "{} -> {} [",
+            node_name_to_dot_id(&tail_node),
+            node_name_to_dot_id(&head_node)
+        )?;
+
+        let values = [
+            (
+                "tail",
+                tail_counter.map(|n| format_counter(n as i64, expected_records)),
+            ),
+            (
+                "head",
+                head_counter.map(|n| format_counter(n as i64, expected_records)),
+            ),
+            (
+                "diff",
+                diff_counter.map(|d| format_counter(d.into(), expected_records)),
+            ),
+        ];
+        let fill = |template: &str| {
+            fill_label_template(template, &values)
+                .ok()
+                .filter(|label| !label.is_empty())
+                .map(|label| dot_escape(&label))
+        };
+
+        for (i, (attr, val)) in tail_counter
+            .filter(|_| show_counters)
+            .and_then(|_| fill(&templates.tail))
+            .map(|l| ("taillabel", format!("{tail_port}{l}")))
+            .into_iter()
+            .chain(
+                head_counter
+                    .filter(|_| show_counters)
+                    .and_then(|_| fill(&templates.head))
+                    .map(|l| ("headlabel", format!("{head_port}{l}"))),
+            )
+            .chain((show_counters && !label.is_empty()).then(|| ("label", label.join(", "))))
+            .chain(diff_counter.map(|d| ("color", palette.edge_color(d, thresholds).to_owned())))
+            // Edges which moved records since the previous poll stand out from dormant ones, unless
+            // they stand out as part of the critical path already.
+            .chain(
+                if critical_edges.contains(&edge_index) {
+                    Some(CRITICAL_PATH_PENWIDTH)
+                } else {
+                    rate.is_some_and(|r| r > 0.0)
+                        .then_some(ACTIVE_EDGE_PENWIDTH)
+                }
+                .map(|penwidth| ("penwidth", penwidth.to_owned())),
+            )
+            // Animated by the style sheet, in the direction of the flow.
+            .chain(
+                rate.is_some_and(|r| r > 0.0)
+                    .then(|| ("class", ACTIVE_EDGE_CLASS.to_owned())),
+            )
+            .enumerate()
+        {
+            if i > 0 {
+                write!(&mut dot, ", ")?;
+            } else {
+                writeln!(&mut dot)?;
+            }
+            writeln!(&mut dot, "{attr} = \"{val}\"",)?;
+        }
+
+        writeln!(&mut dot, "]")?;
+    }
+    writeln!(&mut dot, "}}")?;
+
+    *previous_head_counters = Some((now, head_counters));
+
+    Ok(GraphFrame {
+        dot,
+        nodes: nodes_details,
+        finished,
+        failed_nodes,
+        backed_up,
+    })
+}
+
+/// Hash of what the layout of the graph depends on, unlike its counters which change at every
+/// poll without making the layout any faster.
+pub fn layout_hash(
+    engine: GraphEngine,
+    timeout: Duration,
+    graph: &PipelineGraph,
+    options: &GraphOptions,
+) -> u64 {
+    // Destructured so that an option added later is not forgotten.
+    let GraphOptions {
+        palette,
+        rank_dir,
+        dot_style,
+        edge_thresholds,
+        label_templates,
+        cluster_delimiter,
+        collapsed_clusters,
+        show_counters,
+        expected_records,
+        critical_path,
+        mark,
+        notes,
+        focus,
+    } = options;
+    let mut hasher = DefaultHasher::new();
+    (engine, timeout, &graph.nodes, &graph.edges).hash(&mut hasher);
+    (
+        palette,
+        rank_dir.attr(),
+        dot_style,
+        edge_thresholds,
+        label_templates,
+        cluster_delimiter,
+        collapsed_clusters,
+    )
+        .hash(&mut hasher);
+    (
+        show_counters,
+        expected_records,
+        critical_path,
+        mark,
+        notes,
+        focus,
+    )
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Edges of the longest chain of nodes of the pipeline, by index in `graph.edges`. A pipeline is
+/// not expected to have cycles, but nodes on one are left out rather than looped over.
+fn critical_path(graph: &PipelineGraph) -> BTreeSet<usize> {
+    let mut in_degrees = graph
+        .nodes
+        .iter()
+        .map(|node_name| (node_name.as_str(), 0))
+        .collect::<BTreeMap<_, usize>>();
+    let mut successors = BTreeMap::<&str, Vec<usize>>::new();
+    for (edge_index, (tail_name, _, head_name, _)) in graph.edges.iter().enumerate() {
+        in_degrees.entry(tail_name).or_default();
+        *in_degrees.entry(head_name).or_default() += 1;
+        successors.entry(tail_name).or_default().push(edge_index);
+    }
+
+    // Nodes are visited in topological order, along with the length of the longest chain ending at
+    // them and the last edge of that chain.
+    let mut ready = in_degrees
+        .iter()
+        .filter(|(_, in_degree)| **in_degree == 0)
+        .map(|(node_name, _)| *node_name)
+        .collect::<Vec<_>>();
+    let mut longest = BTreeMap::<&str, (usize, Option<usize>)>::new();
+    let mut visited = Vec::new();
+    while let Some(node_name) = ready.pop() {
+        visited.push(node_name);
+        let (length, _) = *longest.entry(node_name).or_insert((0, None));
+        for &edge_index in successors.get(node_name).into_iter().flatten() {
+            let head_name = graph.edges[edge_index].2.as_str();
+            let head_longest = longest.entry(head_name).or_insert((0, None));
+            if length + 1 > head_longest.0 {
+                *head_longest = (length + 1, Some(edge_index));
+            }
+            if let Some(in_degree) = in_degrees.get_mut(head_name) {
+                *in_degree -= 1;
+                if *in_degree == 0 {
+                    ready.push(head_name);
+                }
+            }
+        }
+    }
+
+    let mut path = BTreeSet::new();
+    let mut current = visited
+        .into_iter()
+        .max_by_key(|node_name| longest.get(node_name).map_or(0, |(length, _)| *length));
+    while let Some(edge_index) = current.and_then(|node_name| longest.get(node_name)?.1) {
+        path.insert(edge_index);
+        current = Some(graph.edges[edge_index].0.as_str());
+    }
+    path
+}
+
+/// Number of nodes and edges found in only one of the compared pipelines.
+#[derive(Clone, Copy, PartialEq)]
+pub struct GraphDiff {
+    pub removed_nodes: usize,
+    pub added_nodes: usize,
+    pub removed_edges: usize,
+    pub added_edges: usize,
+}
+
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.removed_nodes == 0
+            && self.added_nodes == 0
+            && self.removed_edges == 0
+            && self.added_edges == 0
+    }
+}
+
+/// Builds the DOT source of both pipelines merged, what only `left` has being dashed in red and
+/// what only `right` has in green.
+pub fn build_diff_dot(
+    left: &PipelineGraph,
+    right: &PipelineGraph,
+    palette: &Palette,
+) -> Result<(String, GraphDiff), std::fmt::Error> {
+    let mut dot = String::new();
+
+    writeln!(&mut dot, "digraph G {{")?;
+    writeln!(
+        &mut dot,
+        "    graph [bgcolor=\"transparent\", rankdir=\"{}\"];",
+        RankDir::TopToBottom.attr()
+    )?;
+    for element in ["node", "edge"] {
+        writeln!(
+            &mut dot,
+            "    {element} [fontcolor=\"{0}\", color=\"{0}\"];",
+            palette.foreground
+        )?;
+    }
+
+    let mut diff = GraphDiff {
+        removed_nodes: 0,
+        added_nodes: 0,
+        removed_edges: 0,
+        added_edges: 0,
+    };
+    let removed = format!(
+        "color=\"{0}\", fontcolor=\"{0}\", style=\"dashed\"",
+        palette.bad
+    );
+    let added = format!("color=\"{0}\", fontcolor=\"{0}\"", palette.good);
+
+    let left_nodes = left.nodes.iter().collect::<BTreeSet<_>>();
+    let right_nodes = right.nodes.iter().collect::<BTreeSet<_>>();
+    for node_name in left_nodes.union(&right_nodes) {
+        let attrs = match (
+            left_nodes.contains(node_name),
+            right_nodes.contains(node_name),
+        ) {
+            (true, false) => {
+                diff.removed_nodes += 1;
+                removed.as_str()
+            }
+            (false, true) => {
+                diff.added_nodes += 1;
+                added.as_str()
+            }
+            _ => "",
+        };
+        writeln!(&mut dot, "    {} [{attrs}]", node_name_to_dot_id(node_name))?;
+    }
+
+    let left_edges = left.edges.iter().collect::<BTreeSet<_>>();
+    let right_edges = right.edges.iter().collect::<BTreeSet<_>>();
+    for edge in left_edges.union(&right_edges) {
+        let (tail_name, tail_index, head_name, head_index) = edge;
+        let attrs = match (left_edges.contains(edge), right_edges.contains(edge)) {
+            (true, false) => {
+                diff.removed_edges += 1;
+                removed.as_str()
+            }
+            (false, true) => {
+                diff.added_edges += 1;
+                added.as_str()
+            }
+            _ => "",
+        };
+        // The indices tell apart the edges which only moved from one port to another.
+        writeln!(
+            &mut dot,
+            "    {} -> {} [taillabel=\"{tail_index}\", headlabel=\"{head_index}\", {attrs}]",
+            node_name_to_dot_id(tail_name),
+            node_name_to_dot_id(head_name)
+        )?;
+    }
+    writeln!(&mut dot, "}}")?;
+
+    Ok((dot, diff))
+}
+
+/// Whether any counter of a node is lower than it was, which only happens if the process restarted.
+pub fn counters_went_backwards(
+    previous: &BTreeMap<String, NodeDetails>,
+    current: &BTreeMap<String, NodeDetails>,
+) -> bool {
+    current.iter().any(|(node_name, details)| {
+        previous.get(node_name).is_some_and(|previous| {
+            [
+                (&previous.input_read, &details.input_read),
+                (&previous.output_written, &details.output_written),
+            ]
+            .into_iter()
+            .any(|(previous, current)| previous.iter().zip(current).any(|(p, c)| c < p))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label_values(diff: Option<&str>) -> [(&'static str, Option<String>); 3] {
+        [
+            ("tail", Some("12".to_owned())),
+            ("head", Some("10".to_owned())),
+            ("diff", diff.map(str::to_owned)),
+        ]
+    }
+
+    #[test]
+    fn fill_label_template_literal_text() {
+        assert_eq!(
+            fill_label_template("records", &label_values(None)),
+            Ok("records".to_owned())
+        );
+        assert_eq!(
+            fill_label_template("", &label_values(None)),
+            Ok(String::new())
+        );
+    }
+
+    #[test]
+    fn fill_label_template_placeholders() {
+        assert_eq!(
+            fill_label_template("{tail} -> {head}", &label_values(None)),
+            Ok("12 -> 10".to_owned())
+        );
+    }
+
+    #[test]
+    fn fill_label_template_missing_value() {
+        assert_eq!(
+            fill_label_template("{head} ({diff})", &label_values(None)),
+            Ok("10 (?)".to_owned())
+        );
+    }
+
+    #[test]
+    fn fill_label_template_optional_segment() {
+        let template = &EdgeLabelTemplates::default().head;
+        assert_eq!(
+            fill_label_template(template, &label_values(Some("2"))),
+            Ok("10 (2)".to_owned())
+        );
+        assert_eq!(
+            fill_label_template(template, &label_values(None)),
+            Ok("10".to_owned())
+        );
+    }
+
+    #[test]
+    fn fill_label_template_errors() {
+        let values = label_values(None);
+        assert_eq!(
+            fill_label_template("{head", &values),
+            Err("unmatched `{`".to_owned())
+        );
+        assert_eq!(
+            fill_label_template("head}", &values),
+            Err("unmatched `}`".to_owned())
+        );
+        assert_eq!(
+            fill_label_template("{count}", &values),
+            Err("unknown placeholder `{count}`".to_owned())
+        );
+        assert_eq!(
+            fill_label_template("[{diff}", &values),
+            Err("unmatched `[`".to_owned())
+        );
+        assert_eq!(
+            fill_label_template("{diff}]", &values),
+            Err("unmatched `]`".to_owned())
+        );
+        assert_eq!(
+            fill_label_template("[[{diff}]]", &values),
+            Err("nested `[`".to_owned())
+        );
+    }
+
+    fn pipeline(nodes: &[&str], edges: &[(&str, usize, &str, usize)]) -> PipelineGraph {
+        PipelineGraph {
+            nodes: nodes
+                .iter()
+                .map(|node_name| (*node_name).to_owned())
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|(tail_name, tail_index, head_name, head_index)| {
+                    (
+                        (*tail_name).to_owned(),
+                        *tail_index,
+                        (*head_name).to_owned(),
+                        *head_index,
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    fn details(state: NodeState, input_read: &[u64], output_written: &[u64]) -> NodeDetails {
+        NodeDetails {
+            state,
+            input_read: input_read.to_vec(),
+            output_written: output_written.to_vec(),
+        }
+    }
+
+    fn statuses(nodes: &[(&str, NodeDetails)]) -> BTreeMap<String, NodeDetails> {
+        nodes
+            .iter()
+            .map(|(node_name, details)| ((*node_name).to_owned(), details.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn build_dot_node_without_status() {
+        let graph = pipeline(&["a", "b"], &[("a", 0, "b", 0)]);
+        let statuses = statuses(&[("a", details(NodeState::Success, &[], &[5]))]);
+        let frame = build_dot(
+            &graph,
+            &statuses,
+            &GraphOptions::default(),
+            &mut BTreeMap::new(),
+            &mut None,
+        )
+        .unwrap();
+        assert!(frame.nodes["b"].state == NodeState::Unknown);
+        assert!(frame.dot.contains(&format!(
+            "\"b\" [\ncolor = \"{}\"\n, style = \"dashed\"\n",
+            LIGHT_PALETTE.neutral
+        )));
+        assert!(!frame.finished);
+    }
+
+    #[test]
+    fn build_dot_collapsed_cluster() {
+        let graph = pipeline(
+            &["c::x", "c::y", "z"],
+            &[("c::x", 0, "c::y", 0), ("c::y", 0, "z", 0)],
+        );
+        let statuses = statuses(&[
+            ("c::x", details(NodeState::Success, &[], &[])),
+            (
+                "c::y",
+                details(NodeState::Error("boom".to_owned()), &[], &[]),
+            ),
+            ("z", details(NodeState::Success, &[], &[])),
+        ]);
+        let options = GraphOptions {
+            collapsed_clusters: BTreeSet::from(["c".to_owned()]),
+            ..GraphOptions::default()
+        };
+        let frame =
+            build_dot(&graph, &statuses, &options, &mut BTreeMap::new(), &mut None).unwrap();
+        let summary_id = node_name_to_dot_id(&collapsed_node_name("c"));
+        assert!(frame.dot.contains("label = \"c (2)\""));
+        assert!(frame
+            .dot
+            .contains(&format!("color = \"{}\"", LIGHT_PALETTE.bad)));
+        assert!(frame.dot.contains(&format!("{summary_id} -> \"z\" [")));
+        assert!(!frame.dot.contains("\"c::x\""));
+        assert!(!frame.dot.contains(&format!("{summary_id} -> {summary_id}")));
+        assert_eq!(frame.nodes.len(), 3);
+    }
+
+    #[test]
+    fn build_dot_empty_graph() {
+        let frame = build_dot(
+            &pipeline(&[], &[]),
+            &BTreeMap::new(),
+            &GraphOptions::default(),
+            &mut BTreeMap::new(),
+            &mut None,
+        )
+        .unwrap();
+        assert!(frame.dot.starts_with("digraph G {"));
+        assert!(frame.dot.ends_with("}\n"));
+        assert!(frame.nodes.is_empty());
+        assert!(!frame.finished);
+    }
+
+    #[test]
+    fn build_dot_reports_new_failures_once() {
+        let graph = pipeline(&["a"], &[]);
+        let options = GraphOptions::default();
+        let mut history = BTreeMap::new();
+        let mut poll = |state: NodeState| {
+            build_dot(
+                &graph,
+                &statuses(&[("a", details(state, &[], &[]))]),
+                &options,
+                &mut history,
+                &mut None,
+            )
+            .unwrap()
+        };
+
+        // Already failed when connecting.
+        let frame = poll(NodeState::Error("boom".to_owned()));
+        assert!(frame.failed_nodes.is_empty());
+        assert!(frame.finished);
+
+        let frame = poll(NodeState::Running);
+        assert!(frame.failed_nodes.is_empty());
+        assert!(!frame.finished);
+
+        let frame = poll(NodeState::Error("boom".to_owned()));
+        assert_eq!(frame.failed_nodes, vec!["a".to_owned()]);
+
+        let frame = poll(NodeState::Error("boom".to_owned()));
+        assert!(frame.failed_nodes.is_empty());
+    }
+
+    #[test]
+    fn build_dot_edge_labels_and_color() {
+        let graph = pipeline(&["a", "b"], &[("a", 0, "b", 0)]);
+        let statuses = statuses(&[
+            ("a", details(NodeState::Running, &[], &[5])),
+            ("b", details(NodeState::Running, &[3], &[])),
+        ]);
+        let mut history = BTreeMap::new();
+        let frame = build_dot(
+            &graph,
+            &statuses,
+            &GraphOptions::default(),
+            &mut history,
+            &mut None,
+        )
+        .unwrap();
+        assert!(frame.dot.starts_with("digraph G {"));
+        assert!(frame.dot.contains(&format!(
+            "\"a\" -> \"b\" [\ntaillabel = \"5\"\n, headlabel = \"3 (2)\"\n, color = \"{}\"\n]\n",
+            LIGHT_PALETTE.good
+        )));
+        assert!(!frame.finished);
+        assert!(!frame.backed_up);
+        assert!(frame.nodes == statuses);
+        assert_eq!(history["a"].len(), 1);
+    }
+
+    fn diff(
+        removed_nodes: usize,
+        added_nodes: usize,
+        removed_edges: usize,
+        added_edges: usize,
+    ) -> GraphDiff {
+        GraphDiff {
+            removed_nodes,
+            added_nodes,
+            removed_edges,
+            added_edges,
+        }
+    }
+
+    #[test]
+    fn build_diff_dot_same_graph() {
+        let graph = pipeline(&["a", "b"], &[("a", 0, "b", 0)]);
+        let (dot, graph_diff) = build_diff_dot(&graph, &graph, &LIGHT_PALETTE).unwrap();
+        assert!(graph_diff.is_empty());
+        assert!(dot.contains("    \"a\" []\n"));
+        assert!(dot.contains("    \"a\" -> \"b\" [taillabel=\"0\", headlabel=\"0\", ]\n"));
+    }
+
+    #[test]
+    fn build_diff_dot_added_node() {
+        let left = pipeline(&["a"], &[]);
+        let right = pipeline(&["a", "b"], &[]);
+        let (dot, graph_diff) = build_diff_dot(&left, &right, &LIGHT_PALETTE).unwrap();
+        assert!(graph_diff == diff(0, 1, 0, 0));
+        assert!(dot.contains(&format!(
+            "    \"b\" [color=\"{0}\", fontcolor=\"{0}\"]\n",
+            LIGHT_PALETTE.good
+        )));
+    }
+
+    #[test]
+    fn build_diff_dot_removed_node() {
+        let left = pipeline(&["a", "b"], &[]);
+        let right = pipeline(&["a"], &[]);
+        let (dot, graph_diff) = build_diff_dot(&left, &right, &LIGHT_PALETTE).unwrap();
+        assert!(graph_diff == diff(1, 0, 0, 0));
+        assert!(dot.contains(&format!(
+            "    \"b\" [color=\"{0}\", fontcolor=\"{0}\", style=\"dashed\"]\n",
+            LIGHT_PALETTE.bad
+        )));
+    }
+
+    #[test]
+    fn build_diff_dot_moved_edge() {
+        let left = pipeline(&["a", "b", "c"], &[("a", 0, "b", 0)]);
+        let right = pipeline(&["a", "b", "c"], &[("a", 0, "c", 0)]);
+        let (dot, graph_diff) = build_diff_dot(&left, &right, &LIGHT_PALETTE).unwrap();
+        assert!(graph_diff == diff(0, 0, 1, 1));
+        assert!(dot.contains(&format!(
+            "    \"a\" -> \"b\" [taillabel=\"0\", headlabel=\"0\", color=\"{0}\", fontcolor=\"{0}\", style=\"dashed\"]\n",
+            LIGHT_PALETTE.bad
+        )));
+        assert!(dot.contains(&format!(
+            "    \"a\" -> \"c\" [taillabel=\"0\", headlabel=\"0\", color=\"{0}\", fontcolor=\"{0}\"]\n",
+            LIGHT_PALETTE.good
+        )));
+    }
+
+    #[test]
+    fn counters_went_backwards_detection() {
+        let previous = statuses(&[("a", details(NodeState::Running, &[10], &[20, 30]))]);
+        assert!(!counters_went_backwards(&previous, &previous));
+        assert!(!counters_went_backwards(
+            &previous,
+            &statuses(&[("a", details(NodeState::Running, &[11], &[20, 31]))])
+        ));
+        assert!(counters_went_backwards(
+            &previous,
+            &statuses(&[("a", details(NodeState::Running, &[11], &[20, 29]))])
+        ));
+        assert!(counters_went_backwards(
+            &previous,
+            &statuses(&[("a", details(NodeState::Running, &[0], &[]))])
+        ));
+        // Nodes only reported by one of the polls tell nothing.
+        assert!(!counters_went_backwards(
+            &previous,
+            &statuses(&[("b", details(NodeState::Running, &[0], &[0]))])
+        ));
+        assert!(!counters_went_backwards(&BTreeMap::new(), &previous));
+    }
+
+    #[test]
+    fn critical_path_longest_chain() {
+        let graph = pipeline(
+            &["a", "b", "c"],
+            &[("a", 0, "b", 0), ("a", 1, "c", 0), ("b", 0, "c", 1)],
+        );
+        assert_eq!(critical_path(&graph), BTreeSet::from([0, 2]));
+    }
+
+    #[test]
+    fn critical_path_empty() {
+        assert_eq!(critical_path(&pipeline(&[], &[])), BTreeSet::new());
+        assert_eq!(critical_path(&pipeline(&["a"], &[])), BTreeSet::new());
+    }
+
+    #[test]
+    fn critical_path_leaves_cycles_out() {
+        let graph = pipeline(
+            &["a", "b", "c", "d", "e"],
+            &[
+                ("a", 0, "b", 0),
+                ("a", 1, "d", 0),
+                ("b", 0, "c", 0),
+                ("c", 0, "b", 1),
+                ("d", 0, "e", 0),
+            ],
+        );
+        assert_eq!(critical_path(&graph), BTreeSet::from([1, 4]));
+
+        let cycle = pipeline(&["a", "b"], &[("a", 0, "b", 0), ("b", 0, "a", 0)]);
+        assert_eq!(critical_path(&cycle), BTreeSet::new());
+    }
+
+    #[test]
+    fn build_dot_backed_up_edge() {
+        let graph = pipeline(&["a", "b"], &[("a", 0, "b", 0)]);
+        let statuses = statuses(&[
+            ("a", details(NodeState::Success, &[], &[50])),
+            ("b", details(NodeState::Running, &[5], &[])),
+        ]);
+        let frame = build_dot(
+            &graph,
+            &statuses,
+            &GraphOptions::default(),
+            &mut BTreeMap::new(),
+            &mut None,
+        )
+        .unwrap();
+        assert!(frame.backed_up);
+        assert!(frame
+            .dot
+            .contains(&format!("color = \"{}\"\n]", LIGHT_PALETTE.bad)));
+    }
+}
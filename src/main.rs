@@ -1,110 +1,916 @@
-use std::{collections::BTreeMap, fmt::Write, process::Stdio, sync::LazyLock, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    future::Future,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::Path,
+    pin::pin,
+    sync::{LazyLock, OnceLock},
+    time::{Duration, Instant},
+};
 
 use ::quirky_binder_capnp::{discover_processes, Process};
+use base64::prelude::*;
 use dioxus::{document::eval, prelude::*};
-use futures::{AsyncReadExt, AsyncWriteExt};
+use futures::{
+    channel::mpsc::UnboundedReceiver,
+    future::{self, Either},
+    AsyncRead, AsyncReadExt, AsyncWrite, StreamExt,
+};
 use quirky_binder_capnp::quirky_binder_capnp;
 use regex::Regex;
-use smol::{process::Command, Timer};
+use serde::Serialize;
+use smol::{net::TcpStream, Timer};
 use teleop::{
     attach::{attacher::DefaultAttacher, connect},
     operate::capnp::{client_connection, teleop_capnp::teleop::Client},
 };
 
+use crate::{
+    dot::{dot_to_png, dot_to_svg, graphviz_version, DotError, GraphEngine},
+    graph::{
+        build_diff_dot, build_dot, counters_went_backwards, layout_hash, node_cluster,
+        with_background, DotStyle, EdgeLabelTemplates, EdgeThresholds, GraphFrame, GraphOptions,
+        NodeDetails, NodeShape, NodeState, Palette, PipelineGraph, RankDir,
+        DARK_COLOR_BLIND_PALETTE, DARK_PALETTE, DEFAULT_CLUSTER_DELIMITER,
+        LIGHT_COLOR_BLIND_PALETTE, LIGHT_PALETTE,
+    },
+};
+
+mod dot;
+mod graph;
+
 #[derive(Debug, Clone, Routable, PartialEq)]
 #[rustfmt::skip]
 enum Route {
-    #[route("/")]
-    Home {},
-    #[route("/teleop/:pid")]
-    Teleop { pid: u32 },
+    #[layout(TeleopTabs)]
+        #[route("/")]
+        Home {},
+        #[route("/teleop/:pid")]
+        Teleop { pid: u32 },
+        #[route("/remote/:address")]
+        Remote { address: String },
+        #[route("/compare/:left/:right")]
+        Compare { left: u32, right: u32 },
+}
+
+/// What a session is attached to.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Target {
+    /// Local process, attached to by teleop.
+    Pid(u32),
+    /// Teleop server reached over TCP, e.g. the socket of a process on another host forwarded
+    /// with `socat` or `ssh -L`.
+    Address(String),
+}
+
+impl Target {
+    /// Parses a pid, or a `host:port` address.
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if let Ok(pid) = text.parse() {
+            return Some(Target::Pid(pid));
+        }
+        let (host, port) = text.rsplit_once(':')?;
+        (!host.is_empty() && port.parse::<u16>().is_ok()).then(|| Target::Address(text.to_owned()))
+    }
+
+    fn pid(&self) -> Option<u32> {
+        match self {
+            Target::Pid(pid) => Some(*pid),
+            Target::Address(_) => None,
+        }
+    }
+
+    fn title(&self) -> String {
+        match self {
+            Target::Pid(pid) => format!("Process {pid}"),
+            Target::Address(address) => format!("Remote {address}"),
+        }
+    }
+
+    /// Identifies the target in element ids and file names.
+    fn key(&self) -> String {
+        match self {
+            Target::Pid(pid) => pid.to_string(),
+            Target::Address(address) => format!(
+                "remote-{}",
+                address
+                    .chars()
+                    .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                    .collect::<String>()
+            ),
+        }
+    }
+
+    fn route(&self) -> Route {
+        match self {
+            Target::Pid(pid) => Route::Teleop { pid: *pid },
+            Target::Address(address) => Route::Remote {
+                address: address.clone(),
+            },
+        }
+    }
 }
 
+impl std::fmt::Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Target::Pid(pid) => write!(f, "{pid}"),
+            Target::Address(address) => f.write_str(address),
+        }
+    }
+}
+
+/// Title of the window on `Home`, which the sessions prefix with what they are attached to.
+const APP_TITLE: &str = "Quirky Binder Console";
+
 const MAIN_CSS: Asset = asset!("/assets/main.css");
 const TAILWIND_CSS: Asset = asset!("/assets/tailwind.css");
 
+/// Process to open right away, as given with `--pid` on the command line.
+static LAUNCH_PID: OnceLock<u32> = OnceLock::new();
+
+/// Directory to record graph frames to, as given with `--record-dir` on the command line.
+static RECORD_DIR: OnceLock<String> = OnceLock::new();
+
+/// Poll interval of new sessions, as given with `QB_CONSOLE_POLL_MS` in the environment.
+static POLL_INTERVAL_MS: OnceLock<u64> = OnceLock::new();
+
 fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.first().is_some_and(|command| command == "render") {
+        std::process::exit(render_command(&args[1..]));
+    }
+    if let Some(value) = launch_option(&args, "--pid") {
+        match value.parse() {
+            Ok(pid) => {
+                let _ = LAUNCH_PID.set(pid);
+            }
+            Err(_) => eprintln!("Invalid --pid value, expected a process id"),
+        }
+    }
+    if let Some(dir) = launch_option(&args, "--record-dir") {
+        let _ = RECORD_DIR.set(dir);
+    }
+    if let Ok(value) = std::env::var("QB_CONSOLE_POLL_MS") {
+        match value.parse() {
+            Ok(interval) if interval > 0 => {
+                let _ = POLL_INTERVAL_MS.set(interval);
+            }
+            _ => eprintln!("Invalid QB_CONSOLE_POLL_MS value, expected milliseconds"),
+        }
+    }
     dioxus::launch(App);
 }
 
+/// Runs `render --pid <pid> --out <file> [--engine <engine>]` without the GUI, returning the exit
+/// code.
+fn render_command(args: &[String]) -> i32 {
+    let Some(pid) = launch_option(args, "--pid").and_then(|pid| pid.parse().ok()) else {
+        eprintln!("Missing or invalid --pid value, expected a process id");
+        return 2;
+    };
+    let Some(out) = launch_option(args, "--out") else {
+        eprintln!("Missing --out value, expected an SVG or PNG file");
+        return 2;
+    };
+    let engine = match launch_option(args, "--engine") {
+        Some(command) => match GraphEngine::from_command(&command) {
+            Some(engine) => engine,
+            None => {
+                eprintln!("Unknown --engine value {command}");
+                return 2;
+            }
+        },
+        None => GraphEngine::Dot,
+    };
+    match smol::block_on(render(pid, engine, Path::new(&out))) {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("Could not render the graph of process {pid}: {err}");
+            1
+        }
+    }
+}
+
+/// Command attaching to the process the same way as the GUI, in order to tell attach issues apart.
+///
+/// Once attached, it renders the graph to an SVG file in the current directory.
+fn connection_command(pid: u32, engine: GraphEngine) -> String {
+    let exe = std::env::current_exe()
+        .map(|exe| exe.display().to_string())
+        .unwrap_or_else(|_| env!("CARGO_PKG_NAME").to_owned());
+    format!(
+        "{} render --pid {pid} --engine {engine} --out quirky-binder-graph-{pid}.svg",
+        shell_quote(&exe)
+    )
+}
+
+/// Quotes the argument for a POSIX shell, single quotes in it included.
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+/// Returns the value of the option given either as `<name> <value>` or `<name>=<value>`.
+fn launch_option(args: &[String], name: &str) -> Option<String> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == name {
+            let value = args.next().cloned();
+            if value.is_none() {
+                eprintln!("Missing {name} value");
+            }
+            return value;
+        } else if let Some(value) = arg
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(value.to_owned());
+        }
+    }
+    None
+}
+
+#[derive(Clone, Copy, PartialEq)]
 enum AppTheme {
-    #[allow(unused)]
     Light,
-    #[allow(unused)]
     Dark,
     Corporate,
 }
 
+impl AppTheme {
+    const ALL: [AppTheme; 3] = [AppTheme::Light, AppTheme::Dark, AppTheme::Corporate];
+
+    fn name(self) -> &'static str {
+        match self {
+            AppTheme::Light => "light",
+            AppTheme::Dark => "dark",
+            AppTheme::Corporate => "corporate",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "light" => Some(AppTheme::Light),
+            "dark" => Some(AppTheme::Dark),
+            "corporate" => Some(AppTheme::Corporate),
+            _ => None,
+        }
+    }
+
+    fn from_color_scheme(dark: bool) -> Self {
+        if dark {
+            AppTheme::Dark
+        } else {
+            AppTheme::Light
+        }
+    }
+
+    fn palette(self, color_blind: bool) -> &'static Palette {
+        match (self, color_blind) {
+            (AppTheme::Light | AppTheme::Corporate, false) => &LIGHT_PALETTE,
+            (AppTheme::Light | AppTheme::Corporate, true) => &LIGHT_COLOR_BLIND_PALETTE,
+            (AppTheme::Dark, false) => &DARK_PALETTE,
+            (AppTheme::Dark, true) => &DARK_COLOR_BLIND_PALETTE,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            AppTheme::Light => "Light",
+            AppTheme::Dark => "Dark",
+            AppTheme::Corporate => "Corporate",
+        }
+    }
+}
+
+const THEME_STORAGE_KEY: &str = "quirky_binder_console.theme";
+
+const LEGEND_DISMISSED_STORAGE_KEY: &str = "quirky_binder_console.legend_dismissed";
+
+/// Starred process descriptions, as a JSON array of strings.
+const FAVORITES_STORAGE_KEY: &str = "quirky_binder_console.favorites";
+
+const LAST_SESSION_STORAGE_KEY: &str = "quirky_binder_console.last_session";
+
+const NOTIFY_ERRORS_STORAGE_KEY: &str = "quirky_binder_console.notify_errors";
+
+const COLOR_BLIND_STORAGE_KEY: &str = "quirky_binder_console.color_blind";
+
+async fn load_setting(key: &str) -> Option<String> {
+    eval(&format!(
+        r#"
+            return localStorage.getItem("{key}");
+        "#
+    ))
+    .join::<Option<String>>()
+    .await
+    .ok()
+    .flatten()
+}
+
+async fn prefers_dark_color_scheme() -> Option<bool> {
+    eval(
+        r#"
+            return window.matchMedia("(prefers-color-scheme: dark)").matches;
+        "#,
+    )
+    .join::<bool>()
+    .await
+    .ok()
+}
+
+fn store_setting(key: &str, value: &str) {
+    let store = eval(&format!(
+        r#"
+            localStorage.setItem("{key}", await dioxus.recv());
+        "#
+    ));
+    if let Err(err) = store.send(value) {
+        eprintln!("Could not store setting {key}: {err}");
+    }
+}
+
+/// Sets the title of the window, which tells the windows apart in the task switcher.
+fn set_document_title(title: &str) {
+    let update = eval(
+        r#"
+            document.title = await dioxus.recv();
+        "#,
+    );
+    if let Err(err) = update.send(title) {
+        eprintln!("Could not set the window title: {err}");
+    }
+}
+
+/// Title of the window showing a session, e.g. `Process 1234 · my-pipeline — Quirky Binder Console`.
+fn session_title(target: &Target, label: Option<&str>, description: Option<&str>) -> String {
+    let name = label.map_or_else(|| target.title(), str::to_owned);
+    match description {
+        Some(description) => format!("{name} · {description} — {APP_TITLE}"),
+        None => format!("{name} — {APP_TITLE}"),
+    }
+}
+
 #[derive(Clone)]
 struct GlobalState {
     theme: Signal<AppTheme>,
+    /// Short-lived message shown as a toast, whatever the current route.
+    toast: Signal<Option<String>>,
+    /// Graph scale of the processes visited during this session.
+    scales: Signal<BTreeMap<Target, usize>>,
+    /// Processes open in tabs, in opening order.
+    tabs: Signal<Vec<Target>>,
+    legend_dismissed: Signal<bool>,
+    /// Whether the theme follows the system color scheme, until a theme is picked.
+    system_theme: Signal<bool>,
+    /// Whether a desktop notification is shown when a node fails.
+    notify_errors: Signal<bool>,
+    /// Whether the graph uses colors which color-blind people can tell apart, see `Palette`.
+    color_blind: Signal<bool>,
+    /// Version of Graphviz, checked once at startup, or why it could not be told.
+    graphviz_version: Signal<Option<Result<String, String>>>,
+    /// Names given by the user to the targets during this session, shown instead of their title.
+    labels: Signal<BTreeMap<Target, String>>,
 }
 
 #[derive(Clone)]
 struct HomeState {
     pid: Signal<Option<u32>>,
+    /// Consumed by the first `Home` render.
+    launch_pid: Signal<Option<u32>>,
+    search: Signal<String>,
+    sort: Signal<ProcessSort>,
+    auto_refresh: Signal<bool>,
+    /// Descriptions of the starred processes, listed first.
+    favorites: Signal<Vec<String>>,
+    /// Description of the process viewed last, which can be resumed from `Home`.
+    last_session: Signal<Option<String>>,
+    /// Whether the processes are briefly connected to in order to show the state of their nodes.
+    probe_states: Signal<bool>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ProcessSort {
+    PidAscending,
+    PidDescending,
+    Description,
+}
+
+impl ProcessSort {
+    const ALL: [ProcessSort; 3] = [
+        ProcessSort::PidAscending,
+        ProcessSort::PidDescending,
+        ProcessSort::Description,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            ProcessSort::PidAscending => "pid-asc",
+            ProcessSort::PidDescending => "pid-desc",
+            ProcessSort::Description => "description",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|sort| sort.name() == name)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ProcessSort::PidAscending => "Pid ↑",
+            ProcessSort::PidDescending => "Pid ↓",
+            ProcessSort::Description => "Description",
+        }
+    }
+
+    fn sort(self, processes: &mut [Process]) {
+        match self {
+            ProcessSort::PidAscending => processes.sort_by_key(|p| p.pid),
+            ProcessSort::PidDescending => processes.sort_by_key(|p| std::cmp::Reverse(p.pid)),
+            ProcessSort::Description => {
+                processes.sort_by(|a, b| a.description.cmp(&b.description).then(a.pid.cmp(&b.pid)))
+            }
+        }
+    }
 }
 
 #[component]
 fn App() -> Element {
-    let theme = use_signal(|| AppTheme::Corporate);
-    use_context_provider(|| GlobalState { theme });
+    let mut theme = use_signal(|| AppTheme::Corporate);
+    let mut toast = use_signal(|| None);
+    let scales = use_signal(BTreeMap::new);
+    let tabs = use_signal(Vec::new);
+    let mut legend_dismissed = use_signal(|| false);
+    let mut favorites = use_signal(Vec::new);
+    let mut last_session = use_signal(|| None);
+    let mut system_theme = use_signal(|| true);
+    let mut notify_errors = use_signal(|| false);
+    let mut color_blind = use_signal(|| false);
+    let mut graphviz = use_signal(|| None);
+    let labels = use_signal(BTreeMap::new);
+    use_context_provider(|| GlobalState {
+        theme,
+        toast,
+        scales,
+        tabs,
+        legend_dismissed,
+        system_theme,
+        notify_errors,
+        color_blind,
+        graphviz_version: graphviz,
+        labels,
+    });
+    use_future(move || async move {
+        graphviz.set(Some(
+            graphviz_version().await.map_err(|err| err.to_string()),
+        ));
+    });
+
+    // Until the stored settings have been read back, the defaults must not overwrite them.
+    let mut settings_loaded = use_signal(|| false);
+    use_future(move || async move {
+        if let Some(stored) = load_setting(THEME_STORAGE_KEY)
+            .await
+            .as_deref()
+            .and_then(AppTheme::from_name)
+        {
+            theme.set(stored);
+            system_theme.set(false);
+        } else if let Some(dark) = prefers_dark_color_scheme().await {
+            theme.set(AppTheme::from_color_scheme(dark));
+        }
+        if load_setting(LEGEND_DISMISSED_STORAGE_KEY).await.as_deref() == Some("true") {
+            legend_dismissed.set(true);
+        }
+        if let Some(stored) = load_setting(FAVORITES_STORAGE_KEY).await {
+            match serde_json::from_str(&stored) {
+                Ok(stored) => favorites.set(stored),
+                Err(err) => eprintln!("Could not load the favorites: {err}"),
+            }
+        }
+        if let Some(stored) = load_setting(LAST_SESSION_STORAGE_KEY).await {
+            last_session.set(Some(stored));
+        }
+        if load_setting(NOTIFY_ERRORS_STORAGE_KEY).await.as_deref() == Some("true") {
+            notify_errors.set(true);
+        }
+        if load_setting(COLOR_BLIND_STORAGE_KEY).await.as_deref() == Some("true") {
+            color_blind.set(true);
+        }
+        settings_loaded.set(true);
+    });
+
+    use_future(move || async move {
+        let mut changes = eval(
+            r#"
+            window.matchMedia("(prefers-color-scheme: dark)").addEventListener("change", (event) => {
+                dioxus.send(event.matches);
+            });
+        "#,
+        );
+        while let Ok(dark) = changes.recv::<bool>().await {
+            if *system_theme.peek() {
+                theme.set(AppTheme::from_color_scheme(dark));
+            }
+        }
+    });
 
     use_effect(move || {
+        let theme = theme();
         eval(&format!(
             r#"
             document.body.setAttribute("data-theme", "{}");
         "#,
-            match *theme.read() {
-                AppTheme::Light => "light",
-                AppTheme::Dark => "dark",
-                AppTheme::Corporate => "corporate",
-            },
+            theme.name(),
         ));
+        // The system color scheme is followed until a theme is picked explicitly.
+        if settings_loaded() && !system_theme() {
+            store_setting(THEME_STORAGE_KEY, theme.name());
+        }
+    });
+
+    use_effect(move || {
+        let dismissed = legend_dismissed();
+        if settings_loaded() {
+            store_setting(LEGEND_DISMISSED_STORAGE_KEY, &dismissed.to_string());
+        }
+    });
+
+    use_effect(move || {
+        let notify = notify_errors();
+        if settings_loaded() {
+            store_setting(NOTIFY_ERRORS_STORAGE_KEY, &notify.to_string());
+        }
+    });
+
+    use_effect(move || {
+        let color_blind = color_blind();
+        if settings_loaded() {
+            store_setting(COLOR_BLIND_STORAGE_KEY, &color_blind.to_string());
+        }
+    });
+
+    use_effect(move || {
+        let favorites = favorites.read();
+        if settings_loaded() {
+            match serde_json::to_string(&*favorites) {
+                Ok(favorites) => store_setting(FAVORITES_STORAGE_KEY, &favorites),
+                Err(err) => eprintln!("Could not store the favorites: {err}"),
+            }
+        }
+    });
+
+    use_effect(move || {
+        if let Some(shown) = toast() {
+            spawn(async move {
+                Timer::after(Duration::from_secs(5)).await;
+                if toast.peek().as_ref() == Some(&shown) {
+                    toast.set(None);
+                }
+            });
+        }
     });
 
     let pid = use_signal(|| None);
-    use_context_provider(|| HomeState { pid });
+    let launch_pid = use_signal(|| LAUNCH_PID.get().copied());
+    let search = use_signal(String::new);
+    let sort = use_signal(|| ProcessSort::PidAscending);
+    let auto_refresh = use_signal(|| false);
+    let probe_states = use_signal(|| false);
+    use_context_provider(|| HomeState {
+        pid,
+        launch_pid,
+        search,
+        sort,
+        auto_refresh,
+        favorites,
+        last_session,
+        probe_states,
+    });
 
     rsx! {
         document::Link { rel: "stylesheet", href: MAIN_CSS }
         document::Link { rel: "stylesheet", href: TAILWIND_CSS }
         Router::<Route> {}
+        if let Some(message) = toast() {
+            div {
+                class: "toast toast-end",
+                div {
+                    role: "alert",
+                    class: "alert alert-info",
+                    onclick: move |_| {
+                        toast.set(None);
+                    },
+                    "{message}"
+                }
+            }
+        }
     }
 }
 
+const PROCESS_REFRESH_INTERVAL_MS: u64 = 3000;
+
 #[component]
 fn Home() -> Element {
     let nav = navigator();
 
-    let HomeState { pid: mut pid_state } = use_context::<HomeState>();
+    let GlobalState {
+        theme,
+        mut toast,
+        tabs,
+        color_blind,
+        graphviz_version,
+        ..
+    } = use_context::<GlobalState>();
+
+    let HomeState {
+        pid: mut pid_state,
+        mut launch_pid,
+        mut search,
+        mut sort,
+        mut auto_refresh,
+        mut favorites,
+        last_session,
+        mut probe_states,
+    } = use_context::<HomeState>();
 
-    let mut processes = use_signal(|| discover_processes().unwrap());
-    if let Some(pid) = pid_state() {
-        if !processes().iter().any(|p| p.pid == pid) {
+    use_effect(|| set_document_title(APP_TITLE));
+
+    let mut processes = use_signal(discover);
+    // Position in the visible processes of the one selected with the keyboard.
+    let mut highlighted_index = use_signal(|| None::<usize>);
+    // Pid or remote address typed in by the user, connected to whether it was discovered or not.
+    let mut typed_target = use_signal(String::new);
+    // Process picked first for a comparison, until a second one is picked.
+    let mut compare_pid = use_signal(|| None::<u32>);
+    use_future(move || async move {
+        loop {
+            Timer::after(Duration::from_millis(PROCESS_REFRESH_INTERVAL_MS)).await;
+            if auto_refresh() {
+                processes.set(discover());
+            }
+        }
+    });
+    // Worst node state of every process, probed one at a time so that a single connection is open at
+    // once.
+    let mut states = use_signal(BTreeMap::<u32, Option<NodeState>>::new);
+    use_future(move || async move {
+        loop {
+            if *probe_states.peek() {
+                let pids = processes
+                    .peek()
+                    .iter()
+                    .flatten()
+                    .map(|process| process.pid)
+                    .collect::<Vec<_>>();
+                let mut probed = BTreeMap::new();
+                for pid in pids {
+                    match probe_worst_state(pid).await {
+                        Ok(state) => {
+                            probed.insert(pid, state);
+                        }
+                        Err(err) => eprintln!("Could not probe process {pid}: {err}"),
+                    }
+                }
+                states.set(probed);
+            }
+            Timer::after(Duration::from_millis(PROCESS_REFRESH_INTERVAL_MS)).await;
+        }
+    });
+    use_effect(move || {
+        let Some(pid) = launch_pid.write().take() else {
+            return;
+        };
+        if matches!(&*processes.peek(), Ok(processes) if processes.iter().any(|p| p.pid == pid)) {
+            pid_state.set(Some(pid));
+            nav.push(Route::Teleop { pid });
+        } else {
+            toast.set(Some(format!("Process {pid} not found")));
+        }
+    });
+    if let (Some(pid), Ok(processes)) = (pid_state(), &*processes.read()) {
+        if !processes.iter().any(|p| p.pid == pid) {
             pid_state.set(None)
         }
     }
 
+    let mut visible_processes = processes
+        .read()
+        .iter()
+        .flatten()
+        .filter(|process| process_matches(process, &search.read()))
+        .cloned()
+        .collect::<Vec<_>>();
+    sort().sort(&mut visible_processes);
+
+    // Processes sharing their description with others, with their rank by pid and the number of
+    // such processes.
+    let mut pids_by_description = BTreeMap::<&str, Vec<u32>>::new();
+    let processes_read = processes.read();
+    for process in processes_read.iter().flatten() {
+        pids_by_description
+            .entry(&process.description)
+            .or_default()
+            .push(process.pid);
+    }
+    let duplicates = pids_by_description
+        .into_values()
+        .filter(|pids| pids.len() > 1)
+        .flat_map(|mut pids| {
+            pids.sort_unstable();
+            let count = pids.len();
+            pids.into_iter()
+                .enumerate()
+                .map(move |(index, pid)| (pid, (index + 1, count)))
+        })
+        .collect::<BTreeMap<_, _>>();
+    drop(processes_read);
+
+    // The process may have been restarted since, it is then matched by its description only.
+    let last_pid = last_session.read().as_ref().and_then(|last_description| {
+        processes
+            .read()
+            .iter()
+            .flatten()
+            .filter(|process| &process.description == last_description)
+            .map(|process| process.pid)
+            .max()
+    });
+
+    // The sort is stable, so starred processes keep the selected order among themselves.
+    visible_processes.sort_by_key(|process| !is_favorite(&process.description, &favorites.read()));
+
+    let visible_pids = visible_processes
+        .iter()
+        .map(|process| process.pid)
+        .collect::<Vec<_>>();
+    let highlighted_pid = highlighted_index().and_then(|index| visible_pids.get(index).copied());
+    let typed_target_valid = Target::parse(&typed_target.read()).is_some();
+    let mut connect_typed_target = move || {
+        let target = Target::parse(&typed_target.peek());
+        if let Some(target) = target {
+            typed_target.set(String::new());
+            nav.push(target.route());
+        }
+    };
+
     rsx! {
         div {
             class: "home",
+            div {
+                class: "pid-buttons",
+                input {
+                    type: "search",
+                    class: "input",
+                    placeholder: "Filter by description or pid",
+                    value: "{search}",
+                    oninput: move |e| {
+                        search.set(e.value());
+                    },
+                }
+                select {
+                    class: "select w-auto",
+                    "aria-label": "Sort processes",
+                    onchange: move |e| {
+                        if let Some(value) = ProcessSort::from_name(&e.value()) {
+                            sort.set(value);
+                        }
+                    },
+                    for s in ProcessSort::ALL {
+                        option {
+                            key: "{s.name()}",
+                            value: s.name(),
+                            selected: sort() == s,
+                            "{s.label()}"
+                        }
+                    }
+                }
+            }
             div {
                 class: "pid-list",
-                if processes().is_empty() {
+                if let Err(err) = &*processes.read() {
+                    div {
+                        role: "alert",
+                        class: "alert alert-error alert-soft",
+                        "Failed to enumerate processes: {err}"
+                    }
+                } else if processes.read().as_ref().is_ok_and(|processes| processes.is_empty()) {
                     div {
                         "No processes found"
                     }
+                } else if visible_processes.is_empty() {
+                    div {
+                        "No matching processes"
+                    }
                 }
                 ul {
                     class: "list",
-                    for &Process{ pid, ref description } in processes().iter() {
+                    role: "listbox",
+                    "aria-label": "Processes",
+                    tabindex: 0,
+                    onkeydown: move |e| {
+                        let last = visible_pids.len().checked_sub(1);
+                        match e.key() {
+                            Key::ArrowDown => {
+                                e.prevent_default();
+                                highlighted_index.set(match (highlighted_index(), last) {
+                                    (_, None) => None,
+                                    (None, Some(_)) => Some(0),
+                                    (Some(index), Some(last)) => Some((index + 1).min(last)),
+                                });
+                            }
+                            Key::ArrowUp => {
+                                e.prevent_default();
+                                highlighted_index.set(match (highlighted_index(), last) {
+                                    (_, None) => None,
+                                    (None, Some(last)) => Some(last),
+                                    (Some(index), Some(last)) => Some(index.saturating_sub(1).min(last)),
+                                });
+                            }
+                            Key::Enter => {
+                                if let Some(&pid) = highlighted_index().and_then(|index| visible_pids.get(index)) {
+                                    pid_state.set(Some(pid));
+                                    nav.push(Route::Teleop { pid });
+                                }
+                            }
+                            _ => {}
+                        }
+                    },
+                    for &Process{ pid, ref description } in visible_processes.iter() {
                         li {
                             key: "{pid}",
-                            class: "list-row process",
+                            class: if highlighted_pid == Some(pid) { "list-row process bg-base-200" } else { "list-row process" },
+                            role: "option",
+                            "aria-selected": highlighted_pid == Some(pid),
+                            button {
+                                class: "btn btn-ghost btn-sm btn-square",
+                                title: "Star processes with this description",
+                                onclick: {
+                                    let description = description.clone();
+                                    move |_| {
+                                        let mut favorites = favorites.write();
+                                        if is_favorite(&description, &favorites) {
+                                            favorites.retain(|favorite| !favorite_matches(&description, favorite));
+                                        } else {
+                                            favorites.push(description.clone());
+                                        }
+                                    }
+                                },
+                                if is_favorite(description, &favorites.read()) { "★" } else { "☆" }
+                            }
+                            if probe_states() {
+                                {
+                                    match states.read().get(&pid) {
+                                        Some(Some(state)) => rsx! {
+                                            div {
+                                                class: "status",
+                                                title: "{state.label()}",
+                                                style: "background-color: {state.color(theme().palette(color_blind()))};",
+                                            }
+                                        },
+                                        Some(None) => rsx! {
+                                            div { class: "status", title: "No pipeline nodes" }
+                                        },
+                                        None => rsx! {
+                                            div { class: "status status-neutral", title: "Unknown" }
+                                        },
+                                    }
+                                }
+                            }
                             div {
                                 class: "process-description",
+                                title: "{description}",
                                 "{description}"
+                                if let Some((rank, count)) = duplicates.get(&pid) {
+                                    span {
+                                        class: "badge badge-sm badge-warning badge-soft ml-2",
+                                        title: "Other processes have the same description, check the pid",
+                                        "⚠ {rank} of {count}"
+                                    }
+                                }
+                            }
+                            if tabs.read().contains(&Target::Pid(pid)) {
+                                span { class: "badge badge-sm badge-soft", "Open" }
+                            }
+                            button {
+                                class: if compare_pid() == Some(pid) { "btn btn-ghost btn-sm btn-square btn-active" } else { "btn btn-ghost btn-sm btn-square" },
+                                title: match compare_pid() {
+                                    None => "Compare the pipeline with the one of another process".to_owned(),
+                                    Some(left) if left == pid => "Cancel the comparison".to_owned(),
+                                    Some(left) => format!("Compare the pipeline with the one of {left}"),
+                                },
+                                onclick: move |_| {
+                                    match compare_pid() {
+                                        None => compare_pid.set(Some(pid)),
+                                        Some(left) if left == pid => compare_pid.set(None),
+                                        Some(left) => {
+                                            compare_pid.set(None);
+                                            nav.push(Route::Compare { left, right: pid });
+                                        }
+                                    }
+                                },
+                                "⇄"
                             }
                             button {
                                 class: if pid_state() != Some(pid) { "btn" } else { "btn btn-active btn-accent" },
@@ -120,366 +926,3276 @@ fn Home() -> Element {
             }
             div {
                 class: "pid-buttons",
+                if let Some(pid) = last_pid {
+                    button {
+                        class: "btn btn-primary",
+                        title: last_session().unwrap_or_default(),
+                        onclick: move |_| {
+                            pid_state.set(Some(pid));
+                            nav.push(Route::Teleop { pid });
+                        },
+                        "Resume last session ({pid})"
+                    }
+                }
+                div {
+                    class: "join",
+                    input {
+                        type: "text",
+                        class: if !typed_target_valid && !typed_target.read().trim().is_empty() { "input input-error join-item w-40" } else { "input join-item w-40" },
+                        placeholder: "Pid or host:port",
+                        title: "A remote host:port must serve teleop over TCP, e.g. forwarded with socat",
+                        "aria-label": "Pid or address to connect to",
+                        value: "{typed_target}",
+                        oninput: move |e| {
+                            typed_target.set(e.value());
+                        },
+                        onkeydown: move |e| {
+                            if e.key() == Key::Enter {
+                                connect_typed_target();
+                            }
+                        },
+                    }
+                    button {
+                        class: "btn join-item",
+                        disabled: !typed_target_valid,
+                        onclick: move |_| connect_typed_target(),
+                        "Connect"
+                    }
+                }
                 button {
                     class: "btn btn-secondary",
                     onclick: move |_| {
-                        processes.set(discover_processes().unwrap());
+                        processes.set(discover());
                     },
                     "Refresh"
                 }
+                label {
+                    class: "label",
+                    input {
+                        type: "checkbox",
+                        class: "toggle toggle-sm",
+                        checked: auto_refresh(),
+                        onchange: move |e| {
+                            auto_refresh.set(e.checked());
+                        },
+                    }
+                    "Auto-refresh"
+                }
+                label {
+                    class: "label",
+                    title: "Briefly connect to every process to get the state of its pipeline",
+                    input {
+                        type: "checkbox",
+                        class: "toggle toggle-sm",
+                        checked: probe_states(),
+                        onchange: move |e| {
+                            probe_states.set(e.checked());
+                        },
+                    }
+                    "Show states"
+                }
+            }
+            p {
+                class: "text-xs opacity-60",
+                "{graphviz_status(&graphviz_version.read())}"
             }
         }
     }
 }
 
+fn process_description(pid: u32) -> Option<String> {
+    discover()
+        .into_iter()
+        .flatten()
+        .find(|process| process.pid == pid)
+        .map(|process| process.description)
+}
+
+fn discover() -> Result<Vec<Process>, String> {
+    discover_processes().map_err(|err| err.to_string())
+}
+
+/// Whether the process description contains the favorite, an empty favorite matching nothing.
+fn favorite_matches(description: &str, favorite: &str) -> bool {
+    !favorite.is_empty() && description.contains(favorite)
+}
+
+fn is_favorite(description: &str, favorites: &[String]) -> bool {
+    favorites
+        .iter()
+        .any(|favorite| favorite_matches(description, favorite))
+}
+
+fn process_matches(process: &Process, search: &str) -> bool {
+    let search = search.trim().to_lowercase();
+    search.is_empty()
+        || process.description.to_lowercase().contains(&search)
+        || process.pid.to_string().contains(&search)
+}
+
 enum RpcState {
     Connecting,
     Connected,
-    Disconnected,
+    /// Possibly with the reason of the disconnection.
+    Disconnected(Option<String>),
 }
 
-static SVG_SIZE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r#"width="([0-9]+)pt" height="([0-9]+)pt""#).expect("Could not compile RE")
-});
+enum PollCommand {
+    /// Poll the node statuses right away, even while paused.
+    Refresh,
+}
 
+/// Signals shared between the `Teleop` component and its polling session.
+#[derive(Clone, Copy)]
+struct TeleopSession {
+    theme: Signal<AppTheme>,
+    color_blind: Signal<bool>,
+    poll_interval_ms: Signal<u64>,
+    /// Whether the poll interval adapts to the activity of the pipeline.
+    adaptive_polling: Signal<bool>,
+    /// Delay before the next poll, the poll interval unless adapted.
+    poll_delay_ms: Signal<u64>,
+    paused: Signal<bool>,
+    rpc_state: Signal<RpcState>,
+    /// How long to wait for the process to answer before giving up on the connection.
+    connect_timeout_ms: Signal<u64>,
+    engine: Signal<GraphEngine>,
+    /// How long the engine may take to lay the graph out, see `dot_to_svg`.
+    layout_timeout_ms: Signal<u64>,
+    rank_dir: Signal<RankDir>,
+    edge_thresholds: Signal<EdgeThresholds>,
+    label_templates: Signal<EdgeLabelTemplates>,
+    dot_style: Signal<DotStyle>,
+    /// Set when the binary of the selected engine could not be found, until it renders again.
+    missing_engine: Signal<Option<GraphEngine>>,
+    /// Set to the engine which timed out and to the timeout its layout of the graph exceeded, until
+    /// it renders again.
+    layout_timed_out: Signal<Option<(GraphEngine, Duration)>>,
+    /// Latest generated DOT source, kept around for exports.
+    dot: Signal<Option<String>>,
+    svg: Signal<Option<String>>,
+    /// Status of every node as of the latest poll.
+    nodes: Signal<BTreeMap<String, NodeDetails>>,
+    connected_at: Signal<Option<Instant>>,
+    /// Set once all the nodes are done, successfully or not.
+    finished: Signal<bool>,
+    /// Latest connection or polling error, until dismissed.
+    error: Signal<Option<String>>,
+    /// Latest error of a poll to be retried, until the next successful one.
+    warning: Signal<Option<String>>,
+    /// Round-trip time of the latest node statuses request.
+    rtt: Signal<Option<Duration>>,
+    /// Number of nodes and edges of the pipeline.
+    graph_size: Signal<Option<(usize, usize)>>,
+    /// Nodes and edges as of the latest poll, as copied for other tools.
+    snapshot: Signal<Option<GraphSnapshot>>,
+    /// Threshold of backed up edges, while their meaning is explained next to the footer.
+    backlog_hint: Signal<Option<i32>>,
+    /// Whether the meaning of backed up edges was explained already, which is done only once.
+    backlog_explained: Signal<bool>,
+    /// Whether the nodes and edges are shown as tables rather than as a graph, which does not need
+    /// Graphviz.
+    text_view: Signal<bool>,
+    /// Separates the cluster of a node from the rest of its name, clustering is disabled if empty.
+    cluster_delimiter: Signal<String>,
+    /// Clusters rendered as a single summary node.
+    collapsed_clusters: Signal<BTreeSet<String>>,
+    /// Latest states of every node, oldest first, shown as a strip inside the node.
+    history: Signal<BTreeMap<String, VecDeque<NodeState>>>,
+    /// Directory the graph is written to after every poll while recording.
+    record_dir: Signal<String>,
+    recording: Signal<bool>,
+    /// Number of frames written so far, which numbers the next one.
+    recorded_frames: Signal<usize>,
+    /// When the graph was last brought up to date, whether it had to be rendered again or not.
+    updated_at: Signal<Option<Instant>>,
+    /// Whether edges are labelled with their counters and throughput, or only colored.
+    show_counters: Signal<bool>,
+    /// Status of every node when the counters were marked, the differences since then being shown
+    /// alongside the live counters.
+    mark: Signal<Option<BTreeMap<String, NodeDetails>>>,
+    /// Whether edge counters are shown as a percentage of `expected_records`, when known.
+    show_percentages: Signal<bool>,
+    /// Whether the longest chain of nodes of the pipeline is emphasized.
+    show_critical_path: Signal<bool>,
+    /// Records expected to go through every edge, as entered by the user.
+    expected_records: Signal<Option<u64>>,
+    notify_errors: Signal<bool>,
+    /// Notes pinned next to nodes, by node name.
+    notes: Signal<BTreeMap<String, String>>,
+    /// Node shown with its direct neighbors only, the rest of the graph being hidden.
+    focus: Signal<Option<String>>,
+}
+
+/// Number of nodes in each state.
+#[derive(Clone, Copy, Default, PartialEq)]
+struct StateTally {
+    waiting: usize,
+    running: usize,
+    success: usize,
+    error: usize,
+}
+
+impl StateTally {
+    fn of<'a>(nodes: impl IntoIterator<Item = &'a NodeDetails>) -> Self {
+        let mut tally = Self::default();
+        for node in nodes {
+            match node.state {
+                NodeState::Waiting => tally.waiting += 1,
+                NodeState::Running => tally.running += 1,
+                NodeState::Success => tally.success += 1,
+                NodeState::Error(_) => tally.error += 1,
+                NodeState::Unknown => {}
+            }
+        }
+        tally
+    }
+}
+
+const DEFAULT_POLL_INTERVAL_MS: u64 = 3000;
+
+const POLL_INTERVALS_MS: [u64; 6] = [500, 1000, 2000, 3000, 5000, 10000];
+
+/// An adaptive poll interval goes from the selected one divided by that while records flow...
+const ADAPTIVE_POLL_MIN_DIVISOR: u64 = 4;
+
+/// ... to the selected one multiplied by that while the pipeline is idle.
+const ADAPTIVE_POLL_MAX_FACTOR: u64 = 4;
+
+/// Adaptive polling never gets faster than that, whatever the selected interval.
+const MIN_POLL_INTERVAL_MS: u64 = 250;
+
+const DEFAULT_CONNECT_TIMEOUT_MS: u64 = 10000;
+
+/// Longest timeout which can be set in the settings, a day being already more than enough.
+const MAX_TIMEOUT_SECS: u64 = 86400;
+
+/// Longest a layout may take before the engine is killed, which only pathological graphs reach.
+const DEFAULT_LAYOUT_TIMEOUT_MS: u64 = 30000;
+
+/// How long the meaning of backed up edges is explained for, the first time an edge backs up.
+const BACKLOG_HINT_DURATION_SECS: u64 = 15;
+
+const CONNECTION_TIMED_OUT: &str = "Connection timed out";
+
+const PROCESS_EXITED: &str = "Process no longer exists";
+
+const ATTACH_FAILED: &str = "Could not attach to the process, teleop may not be ready yet";
+
+fn teleop_id(target: &Target) -> String {
+    format!("teleop-{}", target.key())
+}
+
+fn graph_container_id(target: &Target) -> String {
+    format!("teleop-svg-{}", target.key())
+}
+
+fn graph_svg_id(target: &Target) -> String {
+    format!("teleop-graph-{}", target.key())
+}
+
+fn minimap_id(target: &Target) -> String {
+    format!("teleop-minimap-{}", target.key())
+}
+
+const KEYBOARD_SHORTCUTS: [(&str, &str); 5] = [
+    ("+", "Zoom in"),
+    ("-", "Zoom out"),
+    ("0", "Reset zoom to 100%"),
+    ("Esc", "Back to the process list"),
+    ("?", "Show or hide this help"),
+];
+
+const MIN_SCALE_PERCENT: usize = 10;
+const MAX_SCALE_PERCENT: usize = 800;
+const SCALE_STEP_PERCENT: usize = 10;
+/// Pause in the dragging of the scale slider after which the graph is resized.
+const SCALE_SLIDER_DEBOUNCE_MS: u64 = 150;
+
+static SVG_SIZE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<svg\b[^>]*?\bwidth="([^"]+)"[^>]*?\bheight="([^"]+)""#)
+        .expect("Could not compile RE")
+});
+
+/// Returns the intrinsic size of the SVG in points, if it can be made sense of.
+fn parse_svg_size(svg: &str) -> Option<(usize, usize)> {
+    let captures = SVG_SIZE_REGEX.captures(svg)?;
+    Some((
+        parse_svg_length(&captures[1])?,
+        parse_svg_length(&captures[2])?,
+    ))
+}
+
+fn parse_svg_length(length: &str) -> Option<usize> {
+    let length = length.trim();
+    let (number, points_per_unit) = if let Some(number) = length.strip_suffix("pt") {
+        (number, 1.0)
+    } else if let Some(number) = length.strip_suffix("in") {
+        (number, 72.0)
+    } else if let Some(number) = length.strip_suffix("px") {
+        (number, 0.75)
+    } else {
+        // Unitless lengths are in user units, i.e. pixels.
+        (length, 0.75)
+    };
+    let points = number.trim().parse::<f64>().ok()? * points_per_unit;
+    (points.is_finite() && points >= 1.0).then(|| points.round() as usize)
+}
+
+/// Keeps the sessions of all the open tabs alive, whatever the current route.
+#[component]
+fn TeleopTabs() -> Element {
+    let GlobalState {
+        mut tabs, labels, ..
+    } = use_context::<GlobalState>();
+
+    let nav = navigator();
+
+    let active_target = match use_route::<Route>() {
+        Route::Home {} | Route::Compare { .. } => None,
+        Route::Teleop { pid } => Some(Target::Pid(pid)),
+        Route::Remote { address } => Some(Target::Address(address)),
+    };
+
+    rsx! {
+        div {
+            class: "teleop-tabs-layout",
+            if !tabs.read().is_empty() {
+                div {
+                    role: "tablist",
+                    class: "tabs tabs-box tabs-sm teleop-tabs",
+                    a {
+                        role: "tab",
+                        class: if active_target.is_none() { "tab tab-active" } else { "tab" },
+                        onclick: move |_| { nav.push(Route::Home {}); },
+                        "Processes"
+                    }
+                    for tab in tabs() {
+                        a {
+                            key: "{tab}",
+                            role: "tab",
+                            class: if active_target.as_ref() == Some(&tab) { "tab tab-active gap-2" } else { "tab gap-2" },
+                            onclick: {
+                                let route = tab.route();
+                                move |_| { nav.push(route.clone()); }
+                            },
+                            if let Some(label) = labels.read().get(&tab) {
+                                "{label}"
+                            } else {
+                                "{tab}"
+                            }
+                            button {
+                                class: "btn btn-xs btn-ghost btn-circle",
+                                "aria-label": "Close",
+                                onclick: {
+                                    let active = active_target.as_ref() == Some(&tab);
+                                    let tab = tab.clone();
+                                    move |e: MouseEvent| {
+                                        e.stop_propagation();
+                                        tabs.write().retain(|other| *other != tab);
+                                        if active {
+                                            match tabs.peek().last() {
+                                                Some(other) => nav.push(other.route()),
+                                                None => nav.push(Route::Home {}),
+                                            };
+                                        }
+                                    }
+                                },
+                                "✕"
+                            }
+                        }
+                    }
+                }
+            }
+            Outlet::<Route> {}
+            for tab in tabs() {
+                TeleopView {
+                    key: "{tab}",
+                    active: active_target.as_ref() == Some(&tab),
+                    target: tab,
+                }
+            }
+        }
+    }
+}
+
+/// Opens the process in a tab, the session itself being rendered by `TeleopTabs`.
 #[component]
 pub fn Teleop(pid: u32) -> Element {
-    let GlobalState { theme } = use_context::<GlobalState>();
+    let GlobalState { mut tabs, .. } = use_context::<GlobalState>();
+    let HomeState {
+        mut last_session, ..
+    } = use_context::<HomeState>();
+
+    use_effect(use_reactive((&pid,), move |(pid,)| {
+        if !tabs.peek().contains(&Target::Pid(pid)) {
+            tabs.write().push(Target::Pid(pid));
+        }
+        if let Some(description) = process_description(pid) {
+            store_setting(LAST_SESSION_STORAGE_KEY, &description);
+            last_session.set(Some(description));
+        }
+    }));
+
+    rsx! {}
+}
+
+/// Opens a remote teleop server in a tab, like `Teleop` does for a local process.
+#[component]
+pub fn Remote(address: String) -> Element {
+    let GlobalState { mut tabs, .. } = use_context::<GlobalState>();
+
+    use_effect(use_reactive((&address,), move |(address,)| {
+        let target = Target::Address(address);
+        if !tabs.peek().contains(&target) {
+            tabs.write().push(target);
+        }
+    }));
+
+    rsx! {}
+}
+
+/// Pipelines of two processes merged into a single graph, so that their structural differences
+/// stand out.
+#[component]
+pub fn Compare(left: u32, right: u32) -> Element {
+    let GlobalState {
+        theme, color_blind, ..
+    } = use_context::<GlobalState>();
+
+    let nav = navigator();
+
+    use_effect(use_reactive((&left, &right), move |(left, right)| {
+        set_document_title(&format!("Compare {left} ⇄ {right} — {APP_TITLE}"));
+    }));
+
+    let comparison = use_resource(use_reactive((&left, &right), move |(left, right)| {
+        let palette = theme().palette(color_blind());
+        async move {
+            let (left_graph, right_graph) =
+                future::try_join(fetch_process_graph(left), fetch_process_graph(right))
+                    .await
+                    .map_err(|err| err.to_string())?;
+            let (dot, diff) = build_diff_dot(&left_graph, &right_graph, palette)
+                .map_err(|err| err.to_string())?;
+            let timeout = Duration::from_millis(DEFAULT_LAYOUT_TIMEOUT_MS);
+            let svg = dot_to_svg(GraphEngine::Dot, &dot, timeout)
+                .await
+                .map_err(|err| err.to_string())?;
+            Ok::<_, String>((svg, diff))
+        }
+    }));
+
+    let palette = theme().palette(color_blind());
+
+    rsx! {
+        div {
+            class: "teleop",
+            div {
+                class: "breadcrumbs text-sm",
+                ul {
+                    li {
+                        a {
+                            onclick: move |_| { nav.push(Route::Home {}); },
+                            "Processes"
+                        }
+                    }
+                    li { "Comparing {left} with {right}" }
+                }
+            }
+            match &*comparison.read() {
+                None => rsx! {
+                    span { class: "loading loading-spinner loading-lg self-center" }
+                },
+                Some(Err(err)) => rsx! {
+                    div {
+                        role: "alert",
+                        class: "alert alert-error alert-soft teleop-alert",
+                        "Could not compare the pipelines: {err}"
+                    }
+                },
+                Some(Ok((svg, diff))) => rsx! {
+                    div {
+                        class: "flex gap-2",
+                        span {
+                            class: "badge badge-sm badge-soft",
+                            style: "color: {palette.bad};",
+                            "Only in {left}: {diff.removed_nodes} nodes, {diff.removed_edges} edges"
+                        }
+                        span {
+                            class: "badge badge-sm badge-soft",
+                            style: "color: {palette.good};",
+                            "Only in {right}: {diff.added_nodes} nodes, {diff.added_edges} edges"
+                        }
+                        if diff.is_empty() {
+                            span { class: "badge badge-sm badge-success badge-soft", "Same structure" }
+                        }
+                    }
+                    div {
+                        class: "teleop-svg",
+                        dangerous_inner_html: "{svg}",
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[component]
+pub fn TeleopView(target: Target, active: bool) -> Element {
+    let GlobalState {
+        mut theme,
+        mut toast,
+        mut scales,
+        mut tabs,
+        mut legend_dismissed,
+        mut system_theme,
+        mut notify_errors,
+        mut color_blind,
+        graphviz_version,
+        mut labels,
+    } = use_context::<GlobalState>();
+
+    // The tab of a target is keyed by it, so that it never changes.
+    let target = use_signal(|| target);
+    // Read once, the description of a process does not change while it runs.
+    let description = use_signal(|| target.peek().pid().and_then(process_description));
+    let label = use_memo(move || labels.read().get(&*target.read()).cloned());
+    // Label being typed in, until it is applied.
+    let mut label_draft = use_signal(|| None::<String>);
+
+    let dot = use_signal(|| None);
+    let svg = use_signal(|| None);
+    let nodes = use_signal(BTreeMap::new);
+    let node_errors = use_memo(move || {
+        nodes
+            .read()
+            .iter()
+            .filter_map(
+                |(node_name, details): (&String, &NodeDetails)| match &details.state {
+                    NodeState::Error(error) => Some((node_name.clone(), error.clone())),
+                    _ => None,
+                },
+            )
+            .collect::<Vec<_>>()
+    });
+
+    let tally = use_memo(move || StateTally::of(nodes.read().values()));
+
+    let mut cluster_delimiter = use_signal(|| DEFAULT_CLUSTER_DELIMITER.to_owned());
+    let mut collapsed_clusters = use_signal(BTreeSet::new);
+    let history = use_signal(BTreeMap::new);
+    let mut record_dir = use_signal(|| RECORD_DIR.get().cloned().unwrap_or_default());
+    let mut recording = use_signal(|| false);
+    let recorded_frames = use_signal(|| 0);
+    let clusters = use_memo(move || {
+        let delimiter = cluster_delimiter();
+        nodes
+            .read()
+            .keys()
+            .filter_map(|node_name| node_cluster(node_name, &delimiter))
+            .map(str::to_owned)
+            .collect::<BTreeSet<_>>()
+    });
+    // Index of the next node in error to jump to.
+    let mut error_cursor = use_signal(|| 0);
+    let mut selected_node = use_signal(|| None::<String>);
+    let selected_node_details = use_memo(move || {
+        selected_node().and_then(|node_name| {
+            nodes
+                .read()
+                .get(&node_name)
+                .map(|details| (node_name, details.clone()))
+        })
+    });
+    let svg_size = use_memo(move || svg().as_ref().and_then(|svg: &String| parse_svg_size(svg)));
+    use_effect(move || {
+        if let Some(svg) = &*svg.read() {
+            show_graph_svg(&target(), svg);
+        }
+    });
+    // The minimap is not interactive, as an image it does not add a second copy of the graph to the
+    // DOM, which is what makes the updates of big graphs stutter.
+    let minimap_src = use_memo(move || {
+        svg.read()
+            .as_ref()
+            .map(|svg| format!("data:image/svg+xml;base64,{}", BASE64_STANDARD.encode(svg)))
+    });
+    let mut scale_percent =
+        use_signal(|| scales.peek().get(&*target.peek()).copied().unwrap_or(100));
+    // Scale of the slider while it is dragged, applied once the slider is released or pauses so
+    // that large graphs are not resized at every step.
+    let mut slider_scale = use_signal(|| None::<usize>);
+    use_effect(move || {
+        let scale = scale_percent();
+        scales.write().insert(target(), scale);
+    });
+    // Scaled in floating point, which cannot overflow however big the graph and the scale.
+    let svg_scaled_size = use_memo(move || {
+        let ratio = scale_percent() as f64 / 100.0;
+        svg_size().map(|(width, height)| {
+            (
+                (width as f64 * ratio).round() as usize,
+                (height as f64 * ratio).round() as usize,
+            )
+        })
+    });
+
+    let mut poll_interval_ms = use_signal(|| {
+        POLL_INTERVAL_MS
+            .get()
+            .copied()
+            .unwrap_or(DEFAULT_POLL_INTERVAL_MS)
+    });
+    let mut adaptive_polling = use_signal(|| false);
+    let poll_delay_ms = use_signal(|| *poll_interval_ms.peek());
+    let mut paused = use_signal(|| false);
+    let mut show_dot = use_signal(|| false);
+    let mut show_shortcuts = use_signal(|| false);
+    // Last pointer position while the graph is being dragged.
+    let mut drag_origin = use_signal(|| None::<(f64, f64)>);
+
+    let rpc_state = use_signal(|| RpcState::Connecting);
+    let mut connect_timeout_ms = use_signal(|| DEFAULT_CONNECT_TIMEOUT_MS);
+    let mut engine = use_signal(|| GraphEngine::Dot);
+    let mut layout_timeout_ms = use_signal(|| DEFAULT_LAYOUT_TIMEOUT_MS);
+    let missing_engine = use_signal(|| None);
+    let layout_timed_out = use_signal(|| None::<(GraphEngine, Duration)>);
+    let mut rank_dir = use_signal(|| RankDir::TopToBottom);
+    let mut edge_thresholds = use_signal(EdgeThresholds::default);
+    let mut label_templates = use_signal(EdgeLabelTemplates::default);
+    let mut dot_style = use_signal(DotStyle::default);
+    let connected_at = use_signal(|| None);
+    let finished = use_signal(|| false);
+    let mut session_error = use_signal(|| None);
+    let warning = use_signal(|| None::<String>);
+    let rtt = use_signal(|| None::<Duration>);
+    let graph_size = use_signal(|| None);
+    let snapshot = use_signal(|| None::<GraphSnapshot>);
+    let mut backlog_hint = use_signal(|| None::<i32>);
+    let backlog_explained = use_signal(|| false);
+    let mut text_view = use_signal(|| false);
+
+    let updated_at = use_signal(|| None::<Instant>);
+    let mut show_counters = use_signal(|| true);
+    let mut mark = use_signal(|| None);
+    let mut show_percentages = use_signal(|| false);
+    let mut show_critical_path = use_signal(|| false);
+    let mut expected_records = use_signal(|| None);
+    let mut notes = use_signal(BTreeMap::new);
+    let mut focus = use_signal(|| None::<String>);
+    // Exports are transparent like the graph on screen unless a color is picked.
+    let mut export_background = use_signal(|| None::<String>);
+    // Age of the graph when it is older than expected, the graph of a finished pipeline being final.
+    let mut stale_for = use_signal(|| None::<Duration>);
+    use_effect(move || {
+        if updated_at().is_some() {
+            stale_for.set(None);
+        }
+    });
+
+    let mut elapsed = use_signal(|| None::<Duration>);
+    use_future(move || async move {
+        loop {
+            if matches!(*rpc_state.peek(), RpcState::Connected) && !*finished.peek() {
+                elapsed.set(connected_at.peek().as_ref().map(Instant::elapsed));
+            }
+            let stale_threshold = Duration::from_millis(2 * *poll_delay_ms.peek());
+            let stale = updated_at
+                .peek()
+                .as_ref()
+                .map(Instant::elapsed)
+                .filter(|age| *age > stale_threshold && !*finished.peek());
+            if *stale_for.peek() != stale {
+                stale_for.set(stale);
+            }
+            Timer::after(Duration::from_secs(1)).await;
+        }
+    });
+
+    let session = TeleopSession {
+        theme,
+        color_blind,
+        poll_interval_ms,
+        adaptive_polling,
+        poll_delay_ms,
+        paused,
+        rpc_state,
+        connect_timeout_ms,
+        engine,
+        layout_timeout_ms,
+        rank_dir,
+        edge_thresholds,
+        label_templates,
+        dot_style,
+        missing_engine,
+        layout_timed_out,
+        dot,
+        svg,
+        nodes,
+        connected_at,
+        finished,
+        error: session_error,
+        warning,
+        rtt,
+        graph_size,
+        snapshot,
+        backlog_hint,
+        backlog_explained,
+        text_view,
+        cluster_delimiter,
+        collapsed_clusters,
+        history,
+        record_dir,
+        recording,
+        recorded_frames,
+        updated_at,
+        show_counters,
+        mark,
+        show_percentages,
+        show_critical_path,
+        expected_records,
+        notify_errors,
+        notes,
+        focus,
+    };
+
+    let state_span = match *rpc_state.read() {
+        RpcState::Connecting => rsx! {
+            div { "aria-label": "status", class: "status status-neutral" }
+        },
+        RpcState::Connected => rsx! {
+            div { "aria-label": "success", class: "status status-success" }
+        },
+        RpcState::Disconnected(_) => rsx! {
+            div { "aria-label": "error", class: "status status-error" }
+        },
+    };
+
+    let mut poller = use_coroutine(move |commands: UnboundedReceiver<PollCommand>| async move {
+        if let Err(err) = run_session(target(), session, commands).await {
+            eprintln!("Could not connect: {err}");
+        }
+    });
+
+    let nav = navigator();
+
+    // Hidden tabs keep polling, only the active one takes the keyboard.
+    use_effect(use_reactive((&active,), move |(active,)| {
+        if active {
+            eval(&format!(
+                r#"
+                    document.getElementById("{}")?.focus();
+                "#,
+                teleop_id(&target())
+            ));
+        }
+    }));
+
+    use_effect(use_reactive((&active,), move |(active,)| {
+        // Read whether the tab is active or not, for the title to follow the renames.
+        let title = session_title(
+            &target.read(),
+            label().as_deref(),
+            description.read().as_deref(),
+        );
+        if active {
+            set_document_title(&title);
+        }
+    }));
+
+    use_effect(move || {
+        if let Some(shown) = backlog_hint() {
+            spawn(async move {
+                Timer::after(Duration::from_secs(BACKLOG_HINT_DURATION_SECS)).await;
+                if *backlog_hint.peek() == Some(shown) {
+                    backlog_hint.set(None);
+                }
+            });
+        }
+    });
+
+    // There is nothing left to reconnect to.
+    use_effect(use_reactive((&active,), move |(active,)| {
+        let exited = matches!(
+            &*rpc_state.read(),
+            RpcState::Disconnected(Some(reason)) if reason == PROCESS_EXITED
+        );
+        if exited {
+            toast.set(Some(format!(
+                "{} no longer exists, returning Home",
+                target.peek().title()
+            )));
+            tabs.write().retain(|tab| *tab != *target.peek());
+            if active {
+                nav.push(Route::Home {});
+            }
+        }
+    }));
+
+    rsx! {
+        div {
+            id: teleop_id(&target()),
+            class: "teleop",
+            style: if !active { "display: none;" },
+            tabindex: -1,
+            onmounted: move |_| async move {
+                let mut keys = eval(&format!(
+                    r#"
+                        const teleop = document.getElementById("{}");
+                        teleop?.focus();
+                        teleop?.addEventListener("keydown", (event) => {{
+                            if (event.ctrlKey || event.altKey || event.metaKey
+                                || event.target.closest("input, textarea, select")) {{
+                                return;
+                            }}
+                            if (["+", "=", "-", "0", "Escape", "?"].includes(event.key)) {{
+                                event.preventDefault();
+                                dioxus.send(event.key);
+                            }}
+                        }});
+                    "#,
+                    teleop_id(&target())
+                ));
+                while let Ok(key) = keys.recv::<String>().await {
+                    match key.as_str() {
+                        "+" | "=" => {
+                            scale_percent.set((scale_percent() + SCALE_STEP_PERCENT).min(MAX_SCALE_PERCENT));
+                        }
+                        "-" => {
+                            scale_percent.set(scale_percent().saturating_sub(SCALE_STEP_PERCENT).max(MIN_SCALE_PERCENT));
+                        }
+                        "0" => {
+                            scale_percent.set(100);
+                        }
+                        "Escape" => {
+                            nav.push(Route::Home {});
+                        }
+                        "?" => {
+                            show_shortcuts.set(!show_shortcuts());
+                        }
+                        _ => {}
+                    }
+                }
+            },
+
+            div {
+                class: "breadcrumbs",
+
+                ul {
+                    li { a {
+                        onclick: move |_| { nav.push(Route::Home {}); },
+                        "Home"
+                    } }
+                    li {
+                        span {
+                            if let Some(draft) = label_draft() {
+                                input {
+                                    class: "input input-xs w-40",
+                                    placeholder: "{target.read().title()}",
+                                    value: "{draft}",
+                                    autofocus: true,
+                                    oninput: move |e| { label_draft.set(Some(e.value())); },
+                                    onkeydown: move |e: KeyboardEvent| {
+                                        match e.key() {
+                                            Key::Enter => {
+                                                let draft = label_draft.take().unwrap_or_default();
+                                                let draft = draft.trim();
+                                                if draft.is_empty() {
+                                                    labels.write().remove(&*target.peek());
+                                                } else {
+                                                    labels.write().insert(target(), draft.to_owned());
+                                                }
+                                            }
+                                            Key::Escape => {
+                                                label_draft.set(None);
+                                            }
+                                            _ => {}
+                                        }
+                                    },
+                                    onblur: move |_| { label_draft.set(None); },
+                                }
+                            } else {
+                                span {
+                                    title: if let Some(description) = &*description.read() { "{description}" },
+                                    if let Some(label) = label() {
+                                        "{label}"
+                                    } else {
+                                        "{target.read().title()}"
+                                    }
+                                }
+                                button {
+                                    class: "btn btn-xs btn-ghost",
+                                    title: "Rename this session, the name being shown in its tab and the window title",
+                                    onclick: move |_| { label_draft.set(Some(label().unwrap_or_default())); },
+                                    "✎"
+                                }
+                            }
+                            {state_span}
+                            if let RpcState::Disconnected(Some(reason)) = &*rpc_state.read() {
+                                span { class: "text-error", "{reason}" }
+                            }
+                            if matches!(*rpc_state.read(), RpcState::Disconnected(_)) {
+                                button {
+                                    class: "btn btn-xs btn-primary",
+                                    onclick: move |_| {
+                                        if target.read().pid().is_none_or(process_exists) {
+                                            poller.restart();
+                                        } else {
+                                            toast.set(Some(format!("{} has exited", target.read().title())));
+                                            tabs.write().retain(|tab| *tab != *target.peek());
+                                            nav.push(Route::Home {});
+                                        }
+                                    },
+                                    "Reconnect"
+                                }
+                            }
+                            if let Target::Pid(pid) = target() {
+                                button {
+                                    class: "btn btn-xs btn-ghost",
+                                    title: "Copy a shell command attaching to the process and rendering its graph to an SVG file",
+                                    onclick: move |_| {
+                                        copy_to_clipboard(&connection_command(pid, engine()));
+                                        toast.set(Some("Copied the connection command".to_owned()));
+                                    },
+                                    "⧉"
+                                }
+                            }
+                            if paused() {
+                                span { class: "badge badge-sm badge-warning", "Paused" }
+                            }
+                            if tally().success > 0 {
+                                span { class: "badge badge-sm badge-success badge-soft", "{tally().success} done" }
+                            }
+                            if tally().running > 0 {
+                                span { class: "badge badge-sm badge-warning badge-soft", "{tally().running} running" }
+                            }
+                            if tally().waiting > 0 {
+                                span { class: "badge badge-sm badge-soft", "{tally().waiting} waiting" }
+                            }
+                            if tally().error > 0 {
+                                span { class: "badge badge-sm badge-error badge-soft", "{tally().error} error" }
+                            }
+                        }
+                    }
+                }
+            }
+            div {
+                class: "teleop-body",
+                if let Some(error) = session_error() {
+                    div {
+                        role: "alert",
+                        class: "alert alert-warning alert-soft teleop-alert",
+                        span { "{error}" }
+                        button {
+                            class: "btn btn-xs btn-ghost",
+                            "aria-label": "Dismiss",
+                            onclick: move |_| session_error.set(None),
+                            "✕"
+                        }
+                    }
+                }
+                if let Some(warning) = warning() {
+                    div {
+                        role: "alert",
+                        class: "alert alert-info alert-soft teleop-alert",
+                        "Could not update the graph, retrying: {warning}"
+                    }
+                }
+                // Polling stops once the pipeline is done, which is told apart from a lost connection.
+                if finished() && !nodes.read().is_empty() {
+                    if tally().error > 0 {
+                        div {
+                            role: "alert",
+                            class: "alert alert-error alert-soft teleop-alert",
+                            "Pipeline finished with {tally().error} failed node(s)"
+                        }
+                    } else {
+                        div {
+                            role: "alert",
+                            class: "alert alert-success alert-soft teleop-alert",
+                            "Pipeline completed successfully"
+                        }
+                    }
+                }
+                if !node_errors.read().is_empty() {
+                    div {
+                        role: "alert",
+                        class: "alert alert-error alert-soft teleop-errors",
+                        ul {
+                            for (node_name, error) in node_errors() {
+                                li {
+                                    key: "{node_name}",
+                                    span { class: "font-bold", "{node_name}" }
+                                    ": {error}"
+                                }
+                            }
+                        }
+                    }
+                }
+                div {
+                    class: "teleop-main",
+                    if let Some(src) = minimap_src().filter(|_| !text_view()) {
+                        Minimap { target: target(), src }
+                    }
+                    if let Some(age) = stale_for() {
+                        span {
+                            class: "badge badge-sm badge-warning badge-soft teleop-stale",
+                            title: "The graph may not reflect the current state of the pipeline",
+                            "stale (updated {age.as_secs()}s ago)"
+                        }
+                    }
+                    if text_view() {
+                        if let Some(snapshot) = snapshot() {
+                            StatusTables {
+                                snapshot,
+                                onselect: move |node_name| {
+                                    selected_node.set(Some(node_name));
+                                },
+                            }
+                        } else {
+                            span { class: "loading loading-spinner loading-lg self-center" }
+                        }
+                    } else if !legend_dismissed() {
+                        GraphLegend {
+                            edge_thresholds: edge_thresholds(),
+                            palette: *theme().palette(color_blind()),
+                            ondismiss: move |_| {
+                                legend_dismissed.set(true);
+                            },
+                        }
+                    }
+                    div {
+                        id: graph_container_id(&target()),
+                        hidden: text_view(),
+                        class: if drag_origin.read().is_some() { "teleop-svg dragging" } else { "teleop-svg" },
+                        onmousedown: move |e| {
+                            e.prevent_default();
+                            let origin = e.client_coordinates();
+                            drag_origin.set(Some((origin.x, origin.y)));
+                        },
+                        onmousemove: move |e| {
+                            if let Some((x, y)) = drag_origin() {
+                                let position = e.client_coordinates();
+                                scroll_graph_by(&target(), x - position.x, y - position.y);
+                                drag_origin.set(Some((position.x, position.y)));
+                            }
+                        },
+                        onmouseup: move |_| {
+                            drag_origin.set(None);
+                        },
+                        onmouseleave: move |_| {
+                            drag_origin.set(None);
+                        },
+                        onwheel: move |e| {
+                            if !e.modifiers().ctrl() {
+                                return;
+                            }
+                            e.prevent_default();
+                            let old_scale = scale_percent();
+                            let new_scale = if e.delta().strip_units().y < 0.0 {
+                                (old_scale + SCALE_STEP_PERCENT).min(MAX_SCALE_PERCENT)
+                            } else {
+                                old_scale.saturating_sub(SCALE_STEP_PERCENT).max(MIN_SCALE_PERCENT)
+                            };
+                            if new_scale != old_scale {
+                                let pointer = e.client_coordinates();
+                                keep_graph_point_under(&target(), pointer.x, pointer.y, new_scale as f64 / old_scale as f64);
+                                scale_percent.set(new_scale);
+                            }
+                        },
+                        onmounted: move |_| async move {
+                            let mut clicks = eval(&format!(
+                                r#"
+                                    const container = document.getElementById("{}");
+                                    // Shift-click or right click copies the node name instead of selecting the node.
+                                    const onNode = (copy) => (event) => {{
+                                        const title = event.target.closest("g.node")?.querySelector("title");
+                                        if (title) {{
+                                            event.preventDefault();
+                                            dioxus.send([title.textContent, copy || event.shiftKey]);
+                                        }}
+                                    }};
+                                    container?.addEventListener("click", onNode(false));
+                                    container?.addEventListener("contextmenu", onNode(true));
+                                "#,
+                                graph_container_id(&target())
+                            ));
+                            while let Ok((node_name, copy)) = clicks.recv::<(String, bool)>().await {
+                                if copy {
+                                    copy_to_clipboard(&node_name);
+                                    toast.set(Some(format!("Copied {node_name}")));
+                                } else {
+                                    selected_node.set(Some(node_name));
+                                }
+                            }
+                        },
+                        if let Some((width, height)) = svg_scaled_size() {
+                            style {
+                                r#"
+                                    #{graph_container_id(&target())} > div > svg {{ width: {width}px; height: {height}px; }}
+                                "#
+                            },
+                        }
+                        if let Some(engine) = missing_engine() {
+                            div {
+                                role: "alert",
+                                class: "alert alert-error alert-soft teleop-alert",
+                                "Graphviz "
+                                code { "{engine}" }
+                                " not found in PATH — install graphviz to view the pipeline graph."
+                            }
+                        } else if let Some((timed_out_engine, timeout)) = layout_timed_out() {
+                            div {
+                                role: "alert",
+                                class: "alert alert-warning alert-soft teleop-alert",
+                                "Graph layout timed out, "
+                                code { "{timed_out_engine}" }
+                                " ran for more than {timeout.as_secs()}s. Try another engine, a longer timeout in the settings, or the text view."
+                            }
+                        } else if dot.read().is_some() && nodes.read().is_empty() {
+                            // The graph is polled but has no node, which renders as a blank SVG.
+                            div {
+                                role: "alert",
+                                class: "alert alert-info alert-soft teleop-alert",
+                                "This process reports no pipeline nodes."
+                            }
+                        } else if svg.read().is_none() && !matches!(*rpc_state.read(), RpcState::Disconnected(_)) {
+                            span { class: "loading loading-spinner loading-lg self-center" }
+                        }
+                        // Filled by `show_graph_svg` rather than rendered, so that it can be patched.
+                        div {
+                            id: graph_svg_id(&target()),
+                            hidden: missing_engine().is_some() || (dot.read().is_some() && nodes.read().is_empty()),
+                        }
+                    }
+                    if let Some((node_name, details)) = selected_node_details() {
+                        NodeDetailsPanel {
+                            note: notes.read().get(&node_name).cloned().unwrap_or_default(),
+                            node_name: node_name.clone(),
+                            details,
+                            onclose: move |_| {
+                                selected_node.set(None);
+                            },
+                            focused: focus.read().as_ref() == Some(&node_name),
+                            onfocus: {
+                                let node_name = node_name.clone();
+                                move |_| {
+                                    if focus.read().as_ref() == Some(&node_name) {
+                                        focus.set(None);
+                                    } else {
+                                        focus.set(Some(node_name.clone()));
+                                    }
+                                    poller.send(PollCommand::Refresh);
+                                }
+                            },
+                            onnote: move |note: String| {
+                                if note.trim().is_empty() {
+                                    notes.write().remove(&node_name);
+                                } else {
+                                    notes.write().insert(node_name.clone(), note);
+                                }
+                                poller.send(PollCommand::Refresh);
+                            },
+                        }
+                    }
+                }
+                if show_dot() {
+                    div {
+                        class: "teleop-dot",
+                        button {
+                            class: "btn btn-xs teleop-dot-copy",
+                            disabled: dot.read().is_none(),
+                            onclick: move |_| {
+                                if let Some(dot) = dot() {
+                                    copy_to_clipboard(&dot);
+                                    toast.set(Some("DOT source copied".to_owned()));
+                                }
+                            },
+                            "Copy DOT"
+                        }
+                        pre {
+                            if let Some(dot) = dot() {
+                                "{dot}"
+                            }
+                        }
+                    }
+                }
+            }
+            if show_shortcuts() {
+                div {
+                    class: "teleop-shortcuts card card-sm bg-base-100 shadow-sm",
+                    div {
+                        class: "card-body",
+                        div { class: "card-title", "Keyboard shortcuts" }
+                        table {
+                            class: "table table-xs",
+                            tbody {
+                                for (keys, action) in KEYBOARD_SHORTCUTS {
+                                    tr {
+                                        key: "{keys}",
+                                        td { kbd { class: "kbd kbd-sm", "{keys}" } }
+                                        td { "{action}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            if let Some(backed_up) = backlog_hint() {
+                div {
+                    role: "status",
+                    class: "alert alert-info alert-soft teleop-alert",
+                    onclick: move |_| backlog_hint.set(None),
+                    if color_blind() {
+                        "Purple edge = large backlog ({backed_up} or more in-flight records)"
+                    } else {
+                        "Red edge = large backlog ({backed_up} or more in-flight records)"
+                    }
+                }
+            }
+            div {
+                class: "teleop-footer",
+                button {
+                    class: if node_errors.read().is_empty() { "btn btn-sm" } else { "btn btn-sm btn-error" },
+                    disabled: node_errors.read().is_empty(),
+                    title: "Jump to the next node in error",
+                    onclick: move |_| {
+                        let errors = node_errors.read();
+                        if !errors.is_empty() {
+                            let index = error_cursor() % errors.len();
+                            let (node_name, _) = &errors[index];
+                            scroll_to_node(&target(), node_name);
+                            selected_node.set(Some(node_name.clone()));
+                            error_cursor.set(index + 1);
+                        }
+                    },
+                    "Errors"
+                    span { class: "badge badge-sm", "{node_errors.read().len()}" }
+                }
+                button {
+                    class: if show_shortcuts() { "btn btn-sm btn-circle btn-active" } else { "btn btn-sm btn-circle" },
+                    title: "Keyboard shortcuts",
+                    onclick: move |_| {
+                        show_shortcuts.set(!show_shortcuts());
+                    },
+                    "?"
+                }
+                if let Some(elapsed) = elapsed() {
+                    span {
+                        class: "teleop-elapsed",
+                        title: "Time elapsed since connection",
+                        "{format_elapsed(elapsed)}"
+                    }
+                }
+                if let Some(rtt) = rtt() {
+                    span {
+                        class: "teleop-elapsed",
+                        title: "Round-trip time of the latest poll",
+                        "RTT {rtt.as_millis()}ms"
+                    }
+                }
+                if let Some((node_count, edge_count)) = graph_size() {
+                    span {
+                        class: "teleop-elapsed",
+                        "{node_count} nodes, {edge_count} edges"
+                    }
+                }
+                input {
+                    type: "range",
+                    min: MIN_SCALE_PERCENT,
+                    max: MAX_SCALE_PERCENT,
+                    step: SCALE_STEP_PERCENT,
+                    value: slider_scale().unwrap_or(scale_percent()),
+                    class: "range range-primary",
+                    oninput: move |e| {
+                        if let Ok(value) = e.value().parse() {
+                            slider_scale.set(Some(value));
+                            spawn(async move {
+                                Timer::after(Duration::from_millis(SCALE_SLIDER_DEBOUNCE_MS)).await;
+                                // Only the latest value is applied.
+                                if *slider_scale.peek() == Some(value) {
+                                    scale_percent.set(value);
+                                }
+                            });
+                        }
+                    },
+                    onchange: move |e| {
+                        if let Ok(value) = e.value().parse() {
+                            scale_percent.set(value);
+                        }
+                        slider_scale.set(None);
+                    },
+                }
+                button {
+                    class: "btn btn-sm",
+                    disabled: svg_size().is_none(),
+                    onclick: move |_| async move {
+                        if let (Some((width, height)), Some((available_width, available_height))) =
+                            (svg_size(), graph_container_size(&target()).await)
+                        {
+                            let ratio = (available_width / width as f64).min(available_height / height as f64);
+                            scale_percent.set(((ratio * 100.0) as usize).clamp(MIN_SCALE_PERCENT, MAX_SCALE_PERCENT));
+                        }
+                    },
+                    "Fit"
+                }
+                button {
+                    class: if paused() { "btn btn-sm btn-warning" } else { "btn btn-sm" },
+                    onclick: move |_| {
+                        paused.set(!paused());
+                        if !paused() {
+                            poller.send(PollCommand::Refresh);
+                        }
+                    },
+                    if paused() { "Resume" } else { "Pause" }
+                }
+                button {
+                    class: "btn btn-sm",
+                    onclick: move |_| {
+                        poller.send(PollCommand::Refresh);
+                    },
+                    "Refresh graph"
+                }
+                button {
+                    class: "btn btn-sm",
+                    disabled: svg.read().is_none(),
+                    onclick: move |_| {
+                        if export_background.read().is_none() {
+                            if let Some(svg) = svg() {
+                                download_graph(&target(), "svg", "image/svg+xml", &svg, false);
+                            }
+                        } else if let Some(dot) = dot() {
+                            spawn(async move {
+                                let timeout = Duration::from_millis(layout_timeout_ms());
+                                match dot_to_svg(engine(), &with_background(&dot, export_background().as_deref()), timeout).await {
+                                    Ok(svg) => {
+                                        download_graph(&target(), "svg", "image/svg+xml", &svg, false);
+                                    }
+                                    Err(err) => {
+                                        toast.set(Some(format!("Could not export SVG: {err}")));
+                                    }
+                                }
+                            });
+                        }
+                    },
+                    "Export SVG"
+                }
+                button {
+                    class: "btn btn-sm",
+                    disabled: dot.read().is_none(),
+                    onclick: move |_| {
+                        if let Some(dot) = dot() {
+                            let dot = with_background(&dot, export_background().as_deref());
+                            spawn(async move {
+                                let timeout = Duration::from_millis(layout_timeout_ms());
+                                match dot_to_png(engine(), &dot, timeout).await {
+                                    Ok(png) => {
+                                        download_graph(&target(), "png", "image/png", &BASE64_STANDARD.encode(png), true);
+                                    }
+                                    Err(err) => {
+                                        toast.set(Some(format!("Could not export PNG: {err}")));
+                                    }
+                                }
+                            });
+                        }
+                    },
+                    "Export PNG"
+                }
+                button {
+                    class: "btn btn-sm",
+                    disabled: snapshot.read().is_none(),
+                    title: "Copy the nodes and edges with their counters as JSON",
+                    onclick: move |_| {
+                        if let Some(snapshot) = &*snapshot.read() {
+                            match serde_json::to_string_pretty(snapshot) {
+                                Ok(json) => {
+                                    copy_to_clipboard(&json);
+                                    toast.set(Some("State copied as JSON".to_owned()));
+                                }
+                                Err(err) => {
+                                    toast.set(Some(format!("Could not serialize the state: {err}")));
+                                }
+                            }
+                        }
+                    },
+                    "Copy JSON"
+                }
+                button {
+                    class: if recording() { "btn btn-sm btn-error" } else { "btn btn-sm" },
+                    disabled: record_dir.read().trim().is_empty(),
+                    title: "Write the graph to the record directory after every poll",
+                    onclick: move |_| {
+                        recording.set(!recording());
+                    },
+                    if recording() { "● REC {recorded_frames}" } else { "Record" }
+                }
+                label {
+                    class: "label",
+                    input {
+                        type: "checkbox",
+                        class: "toggle toggle-sm",
+                        checked: show_counters(),
+                        onchange: move |e| {
+                            show_counters.set(e.checked());
+                            poller.send(PollCommand::Refresh);
+                        },
+                    }
+                    "Show counters"
+                }
+                if let Some(focused) = focus() {
+                    button {
+                        class: "btn btn-sm btn-active",
+                        title: "Focused on {focused}",
+                        onclick: move |_| {
+                            focus.set(None);
+                            poller.send(PollCommand::Refresh);
+                        },
+                        "Clear focus"
+                    }
+                }
+                button {
+                    class: if mark.read().is_some() { "btn btn-sm btn-active" } else { "btn btn-sm" },
+                    disabled: nodes.read().is_empty() && mark.read().is_none(),
+                    title: "Show the records processed since now alongside the counters",
+                    onclick: move |_| {
+                        if mark.read().is_some() {
+                            mark.set(None);
+                        } else {
+                            mark.set(Some(nodes()));
+                        }
+                        poller.send(PollCommand::Refresh);
+                    },
+                    if mark.read().is_some() { "Clear mark" } else { "Mark" }
+                }
+                label {
+                    class: "label",
+                    input {
+                        type: "checkbox",
+                        class: "toggle toggle-sm",
+                        checked: show_percentages(),
+                        onchange: move |e| {
+                            show_percentages.set(e.checked());
+                            poller.send(PollCommand::Refresh);
+                        },
+                    }
+                    "Percentages"
+                }
+                if show_percentages() {
+                    input {
+                        type: "number",
+                        class: "input input-sm w-32",
+                        min: 1,
+                        placeholder: "Expected records",
+                        value: expected_records().map(|records| records.to_string()).unwrap_or_default(),
+                        onchange: move |e| {
+                            expected_records.set(e.value().parse().ok().filter(|records| *records > 0));
+                            poller.send(PollCommand::Refresh);
+                        },
+                    }
+                }
+                button {
+                    class: if show_dot() { "btn btn-sm btn-active" } else { "btn btn-sm" },
+                    onclick: move |_| {
+                        show_dot.set(!show_dot());
+                    },
+                    if show_dot() { "Hide DOT" } else { "Show DOT" }
+                }
+                button {
+                    class: if text_view() { "btn btn-sm btn-active" } else { "btn btn-sm" },
+                    title: "Show the nodes and edges as tables, which does not need Graphviz",
+                    onclick: move |_| {
+                        text_view.set(!text_view());
+                        poller.send(PollCommand::Refresh);
+                    },
+                    if text_view() { "Graph view" } else { "Text view" }
+                }
+                div {
+                    class: "join",
+                    "aria-label": "Graph direction",
+                    for r in RankDir::ALL {
+                        button {
+                            key: "{r.attr()}",
+                            class: if rank_dir() != r { "btn btn-sm join-item" } else { "btn btn-sm join-item btn-active btn-accent" },
+                            title: r.attr(),
+                            onclick: move |_| {
+                                rank_dir.set(r);
+                                poller.send(PollCommand::Refresh);
+                            },
+                            "{r.label()}"
+                        }
+                    }
+                }
+                select {
+                    class: "select select-sm w-auto",
+                    "aria-label": "Layout engine",
+                    onchange: move |e| {
+                        if let Some(value) = GraphEngine::from_command(&e.value()) {
+                            engine.set(value);
+                            poller.send(PollCommand::Refresh);
+                        }
+                    },
+                    for e in GraphEngine::ALL {
+                        option {
+                            key: "{e}",
+                            value: e.command(),
+                            selected: engine() == e,
+                            "{e}"
+                        }
+                    }
+                }
+                select {
+                    class: "select select-sm w-auto",
+                    "aria-label": "Poll interval",
+                    onchange: move |e| {
+                        if let Ok(value) = e.value().parse() {
+                            poll_interval_ms.set(value);
+                        }
+                    },
+                    for interval in POLL_INTERVALS_MS {
+                        option {
+                            key: "{interval}",
+                            value: "{interval}",
+                            selected: poll_interval_ms() == interval,
+                            if interval < 1000 { "Every {interval}ms" } else { "Every {interval / 1000}s" }
+                        }
+                    }
+                    if !POLL_INTERVALS_MS.contains(&poll_interval_ms()) {
+                        option {
+                            value: "{poll_interval_ms}",
+                            selected: true,
+                            "Every {poll_interval_ms}ms"
+                        }
+                    }
+                }
+                label {
+                    class: "label",
+                    title: if adaptive_polling() {
+                        format!("Polling faster while records flow, slower while idle, next poll in {}ms", poll_delay_ms())
+                    } else {
+                        "Poll faster while records flow, slower while idle".to_owned()
+                    },
+                    input {
+                        type: "checkbox",
+                        class: "toggle toggle-sm",
+                        checked: adaptive_polling(),
+                        onchange: move |e| {
+                            adaptive_polling.set(e.checked());
+                        },
+                    }
+                    "Adaptive"
+                }
+                div {
+                    class: "join",
+                    for t in AppTheme::ALL {
+                        button {
+                            key: "{t.name()}",
+                            class: if theme() != t { "btn btn-sm join-item" } else { "btn btn-sm join-item btn-active btn-accent" },
+                            onclick: move |_| {
+                                system_theme.set(false);
+                                theme.set(t);
+                            },
+                            "{t.label()}"
+                        }
+                    }
+                }
+                div {
+                    class: "dropdown dropdown-top dropdown-end",
+                    div {
+                        tabindex: 0,
+                        role: "button",
+                        class: "btn btn-sm",
+                        "Settings"
+                    }
+                    div {
+                        tabindex: 0,
+                        class: "dropdown-content card card-sm bg-base-100 shadow-sm teleop-settings",
+                        div {
+                            class: "card-body",
+                            fieldset {
+                                class: "fieldset",
+                                legend { class: "fieldset-legend", "Edge colors" }
+                                p {
+                                    class: "label teleop-settings-help",
+                                    "In-flight records across an edge: written by the tail node but not yet read by the head node."
+                                }
+                                label {
+                                    class: "label",
+                                    if color_blind() { "Busy (yellow) from" } else { "Busy (orange) from" }
+                                }
+                                input {
+                                    type: "number",
+                                    class: "input input-sm",
+                                    min: 1,
+                                    max: edge_thresholds().backed_up - 1,
+                                    value: edge_thresholds().busy,
+                                    onchange: move |e| {
+                                        if let Ok(value) = e.value().parse::<i32>() {
+                                            // Edges are busy before they are backed up.
+                                            let backed_up = edge_thresholds.peek().backed_up;
+                                            edge_thresholds.write().busy = value.clamp(1, backed_up - 1);
+                                            poller.send(PollCommand::Refresh);
+                                        }
+                                    },
+                                }
+                                label {
+                                    class: "label",
+                                    if color_blind() { "Backed up (purple) from" } else { "Backed up (red) from" }
+                                }
+                                input {
+                                    type: "number",
+                                    class: "input input-sm",
+                                    min: edge_thresholds().busy + 1,
+                                    value: edge_thresholds().backed_up,
+                                    onchange: move |e| {
+                                        if let Ok(value) = e.value().parse::<i32>() {
+                                            let busy = edge_thresholds.peek().busy;
+                                            edge_thresholds.write().backed_up = value.max(busy + 1);
+                                            poller.send(PollCommand::Refresh);
+                                        }
+                                    },
+                                }
+                                label {
+                                    class: "label",
+                                    input {
+                                        type: "checkbox",
+                                        class: "toggle toggle-sm",
+                                        checked: !legend_dismissed(),
+                                        onchange: move |e| {
+                                            legend_dismissed.set(!e.checked());
+                                        },
+                                    }
+                                    "Show legend"
+                                }
+                                label {
+                                    class: "label",
+                                    title: "Blue, yellow and purple, with node borders telling the states apart",
+                                    input {
+                                        type: "checkbox",
+                                        class: "toggle toggle-sm",
+                                        checked: color_blind(),
+                                        onchange: move |e| {
+                                            color_blind.set(e.checked());
+                                            poller.send(PollCommand::Refresh);
+                                        },
+                                    }
+                                    "Color-blind friendly"
+                                }
+                            }
+                            fieldset {
+                                class: "fieldset",
+                                legend { class: "fieldset-legend", "Edge labels" }
+                                p {
+                                    class: "label teleop-settings-help",
+                                    "Placeholders: {{tail}} records written, {{head}} records read, {{diff}} records in flight. Text within [ ] is left out when a value is unknown."
+                                }
+                                label {
+                                    class: "label",
+                                    "Tail"
+                                }
+                                input {
+                                    type: "text",
+                                    class: "input input-sm",
+                                    value: "{label_templates().tail}",
+                                    onchange: move |e| {
+                                        label_templates.write().tail = e.value();
+                                        poller.send(PollCommand::Refresh);
+                                    },
+                                }
+                                label {
+                                    class: "label",
+                                    "Head"
+                                }
+                                input {
+                                    type: "text",
+                                    class: "input input-sm",
+                                    value: "{label_templates().head}",
+                                    onchange: move |e| {
+                                        label_templates.write().head = e.value();
+                                        poller.send(PollCommand::Refresh);
+                                    },
+                                }
+                                if let Err(err) = label_templates.read().validate() {
+                                    p {
+                                        class: "label text-error teleop-settings-help",
+                                        "Invalid template, {err}: the default labels are shown."
+                                    }
+                                }
+                                button {
+                                    class: "btn btn-xs",
+                                    onclick: move |_| {
+                                        label_templates.set(EdgeLabelTemplates::default());
+                                        poller.send(PollCommand::Refresh);
+                                    },
+                                    "Reset"
+                                }
+                            }
+                            fieldset {
+                                class: "fieldset",
+                                legend { class: "fieldset-legend", "Style" }
+                                label {
+                                    class: "label",
+                                    "Font"
+                                }
+                                input {
+                                    type: "text",
+                                    class: "input input-sm",
+                                    placeholder: "Graphviz default",
+                                    value: "{dot_style.read().font_name}",
+                                    onchange: move |e| {
+                                        dot_style.write().font_name = e.value();
+                                        poller.send(PollCommand::Refresh);
+                                    },
+                                }
+                                label {
+                                    class: "label",
+                                    "Font size"
+                                }
+                                input {
+                                    type: "number",
+                                    class: "input input-sm",
+                                    min: 1,
+                                    placeholder: "Graphviz default",
+                                    value: dot_style.read().font_size.map(|size| size.to_string()).unwrap_or_default(),
+                                    onchange: move |e| {
+                                        dot_style.write().font_size = e.value().parse().ok().filter(|size| *size > 0);
+                                        poller.send(PollCommand::Refresh);
+                                    },
+                                }
+                                label {
+                                    class: "label",
+                                    "Node shape"
+                                }
+                                select {
+                                    class: "select select-sm",
+                                    onchange: move |e| {
+                                        dot_style.write().node_shape = NodeShape::from_attr(&e.value());
+                                        poller.send(PollCommand::Refresh);
+                                    },
+                                    option {
+                                        value: "",
+                                        selected: dot_style.read().node_shape.is_none(),
+                                        "Graphviz default"
+                                    }
+                                    for shape in NodeShape::ALL {
+                                        option {
+                                            key: "{shape.attr()}",
+                                            value: shape.attr(),
+                                            selected: dot_style.read().node_shape == Some(shape),
+                                            "{shape.attr()}"
+                                        }
+                                    }
+                                }
+                                label {
+                                    class: "label",
+                                    title: "Emphasize the longest chain of nodes, which usually bounds the end-to-end latency",
+                                    input {
+                                        type: "checkbox",
+                                        class: "toggle toggle-sm",
+                                        checked: show_critical_path(),
+                                        onchange: move |e| {
+                                            show_critical_path.set(e.checked());
+                                            poller.send(PollCommand::Refresh);
+                                        },
+                                    }
+                                    "Critical path"
+                                }
+                            }
+                            fieldset {
+                                class: "fieldset",
+                                legend { class: "fieldset-legend", "Clusters" }
+                                p {
+                                    class: "label teleop-settings-help",
+                                    "Nodes whose names share a prefix up to the delimiter are boxed together. Leave empty to disable."
+                                }
+                                label {
+                                    class: "label",
+                                    "Delimiter"
+                                }
+                                input {
+                                    type: "text",
+                                    class: "input input-sm",
+                                    value: "{cluster_delimiter}",
+                                    onchange: move |e| {
+                                        cluster_delimiter.set(e.value());
+                                        poller.send(PollCommand::Refresh);
+                                    },
+                                }
+                                for cluster in clusters() {
+                                    label {
+                                        key: "{cluster}",
+                                        class: "label",
+                                        input {
+                                            type: "checkbox",
+                                            class: "toggle toggle-sm",
+                                            checked: collapsed_clusters.read().contains(&cluster),
+                                            onchange: {
+                                                let cluster = cluster.clone();
+                                                move |e: FormEvent| {
+                                                    if e.checked() {
+                                                        collapsed_clusters.write().insert(cluster.clone());
+                                                    } else {
+                                                        collapsed_clusters.write().remove(&cluster);
+                                                    }
+                                                    poller.send(PollCommand::Refresh);
+                                                }
+                                            },
+                                        }
+                                        "Collapse {cluster}"
+                                    }
+                                }
+                            }
+                            fieldset {
+                                class: "fieldset",
+                                legend { class: "fieldset-legend", "Connection" }
+                                label {
+                                    class: "label",
+                                    "Timeout (seconds)"
+                                }
+                                input {
+                                    type: "number",
+                                    class: "input input-sm",
+                                    min: 1,
+                                    max: MAX_TIMEOUT_SECS,
+                                    value: connect_timeout_ms() / 1000,
+                                    onchange: move |e| {
+                                        if let Ok(value) = e.value().parse::<u64>() {
+                                            let secs = value.clamp(1, MAX_TIMEOUT_SECS);
+                                            connect_timeout_ms.set(secs * 1000);
+                                        }
+                                    },
+                                }
+                            }
+                            fieldset {
+                                class: "fieldset",
+                                legend { class: "fieldset-legend", "Layout" }
+                                label {
+                                    class: "label",
+                                    "Timeout (seconds)"
+                                }
+                                input {
+                                    type: "number",
+                                    class: "input input-sm",
+                                    min: 1,
+                                    max: MAX_TIMEOUT_SECS,
+                                    value: layout_timeout_ms() / 1000,
+                                    onchange: move |e| {
+                                        if let Ok(value) = e.value().parse::<u64>() {
+                                            let secs = value.clamp(1, MAX_TIMEOUT_SECS);
+                                            layout_timeout_ms.set(secs * 1000);
+                                        }
+                                    },
+                                }
+                                p {
+                                    class: "label teleop-settings-help",
+                                    "The engine is stopped when it takes longer to lay the graph out."
+                                }
+                            }
+                            fieldset {
+                                class: "fieldset",
+                                legend { class: "fieldset-legend", "Recording" }
+                                label {
+                                    class: "label",
+                                    "Directory"
+                                }
+                                input {
+                                    type: "text",
+                                    class: "input input-sm",
+                                    placeholder: "/path/to/frames",
+                                    value: "{record_dir}",
+                                    onchange: move |e| {
+                                        record_dir.set(e.value());
+                                    },
+                                }
+                            }
+                            fieldset {
+                                class: "fieldset",
+                                legend { class: "fieldset-legend", "Export" }
+                                label {
+                                    class: "label",
+                                    "Background"
+                                }
+                                div {
+                                    class: "flex gap-2",
+                                    select {
+                                        class: "select select-sm",
+                                        onchange: move |e| {
+                                            let value = e.value();
+                                            export_background.set((value != "transparent").then_some(value));
+                                        },
+                                        for color in ["transparent", "white", "black"] {
+                                            option {
+                                                key: "{color}",
+                                                value: color,
+                                                selected: export_background().as_deref().unwrap_or("transparent") == color,
+                                                "{color}"
+                                            }
+                                        }
+                                        if let Some(custom) = export_background().filter(|color| color != "white" && color != "black") {
+                                            option { value: "{custom}", selected: true, "{custom}" }
+                                        }
+                                    }
+                                    input {
+                                        type: "color",
+                                        class: "input input-sm w-12 p-1",
+                                        title: "Custom background",
+                                        value: export_background().filter(|color| color.starts_with('#')).unwrap_or_else(|| "#ffffff".to_owned()),
+                                        onchange: move |e| {
+                                            export_background.set(Some(e.value()));
+                                        },
+                                    }
+                                }
+                            }
+                            fieldset {
+                                class: "fieldset",
+                                legend { class: "fieldset-legend", "Notifications" }
+                                label {
+                                    class: "label",
+                                    input {
+                                        type: "checkbox",
+                                        class: "toggle toggle-sm",
+                                        checked: notify_errors(),
+                                        onchange: move |e| async move {
+                                            if !e.checked() {
+                                                notify_errors.set(false);
+                                            } else if request_notification_permission().await {
+                                                notify_errors.set(true);
+                                            } else {
+                                                notify_errors.set(false);
+                                                toast.set(Some("Notifications are not allowed".to_owned()));
+                                            }
+                                        },
+                                    }
+                                    "Notify when a node fails"
+                                }
+                            }
+                            p {
+                                class: "text-xs opacity-60",
+                                "{graphviz_status(&graphviz_version.read())}"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Version of Graphviz as shown to the user, which helps telling why layouts differ from one
+/// machine to another.
+fn graphviz_status(version: &Option<Result<String, String>>) -> String {
+    match version {
+        None => "Checking Graphviz…".to_owned(),
+        Some(Ok(version)) => format!("Graphviz {version}"),
+        Some(Err(err)) => err.clone(),
+    }
+}
+
+/// Triggers a download of `content` as `quirky-binder-graph-<target>-<local timestamp>.<extension>`.
+///
+/// Binary content must be base64 encoded.
+fn download_graph(target: &Target, extension: &str, mime_type: &str, content: &str, base64: bool) {
+    let download = eval(
+        r#"
+            const [key, extension, mimeType, encoded, base64] = await dioxus.recv();
+            const content = base64 ? Uint8Array.from(atob(encoded), (c) => c.charCodeAt(0)) : encoded;
+            const now = new Date();
+            const pad = (n) => String(n).padStart(2, "0");
+            const timestamp = `${now.getFullYear()}${pad(now.getMonth() + 1)}${pad(now.getDate())}`
+                + `-${pad(now.getHours())}${pad(now.getMinutes())}${pad(now.getSeconds())}`;
+            const url = URL.createObjectURL(new Blob([content], { type: mimeType }));
+            const anchor = document.createElement("a");
+            anchor.href = url;
+            anchor.download = `quirky-binder-graph-${key}-${timestamp}.${extension}`;
+            document.body.appendChild(anchor);
+            anchor.click();
+            anchor.remove();
+            URL.revokeObjectURL(url);
+        "#,
+    );
+    if let Err(err) = download.send((target.key(), extension, mime_type, content, base64)) {
+        eprintln!("Could not export graph: {err}");
+    }
+}
+
+/// Shows `svg` as the graph of the process.
+///
+/// Between polls, the layout of the graph usually stays the same and only its labels and colors
+/// change: the elements on screen are then patched in place, which is much cheaper than rebuilding
+/// the whole graph and keeps big graphs smooth. A graph of another shape replaces the previous one.
+fn show_graph_svg(target: &Target, svg: &str) {
+    let show = eval(&format!(
+        r#"
+            const svg = await dioxus.recv();
+            const target = document.getElementById("{}");
+            if (target) {{
+                const next = new DOMParser().parseFromString(svg, "image/svg+xml").documentElement;
+                const current = target.querySelector("svg");
+                const sameShape = (a, b) =>
+                    a.tagName === b.tagName &&
+                    a.children.length === b.children.length &&
+                    [...a.children].every((child, i) => sameShape(child, b.children[i]));
+                const patch = (a, b) => {{
+                    for (const attr of [...a.attributes]) {{
+                        if (!b.hasAttribute(attr.name)) {{
+                            a.removeAttribute(attr.name);
+                        }}
+                    }}
+                    for (const attr of b.attributes) {{
+                        if (a.getAttribute(attr.name) !== attr.value) {{
+                            a.setAttribute(attr.name, attr.value);
+                        }}
+                    }}
+                    if (b.children.length === 0) {{
+                        if (a.textContent !== b.textContent) {{
+                            a.textContent = b.textContent;
+                        }}
+                    }} else {{
+                        [...a.children].forEach((child, i) => patch(child, b.children[i]));
+                    }}
+                }};
+                if (current && next.tagName === "svg" && sameShape(current, next)) {{
+                    patch(current, next);
+                }} else {{
+                    // Replacing the graph scrolls the container back to the top left corner,
+                    // unless the offset is put back, which is only best-effort if the layout changed.
+                    const container = document.getElementById("{}");
+                    const left = container?.scrollLeft ?? 0;
+                    const top = container?.scrollTop ?? 0;
+                    target.innerHTML = svg;
+                    container?.scrollTo(left, top);
+                }}
+            }}
+        "#,
+        graph_svg_id(target),
+        graph_container_id(target)
+    ));
+    if let Err(err) = show.send(svg) {
+        eprintln!("Could not show the graph: {err}");
+    }
+}
+
+fn scroll_graph_by(target: &Target, dx: f64, dy: f64) {
+    eval(&format!(
+        r#"
+            document.getElementById("{}")?.scrollBy({dx}, {dy});
+        "#,
+        graph_container_id(target)
+    ));
+}
+
+/// Scrolls the graph so that the node is centered, and briefly highlights it.
+fn scroll_to_node(target: &Target, node_name: &str) {
+    let scroll = eval(&format!(
+        r#"
+            const nodeName = await dioxus.recv();
+            const container = document.getElementById("{}");
+            const node = [...(container?.querySelectorAll("g.node") ?? [])]
+                .find((g) => g.querySelector("title")?.textContent === nodeName);
+            if (node) {{
+                const containerRect = container.getBoundingClientRect();
+                const nodeRect = node.getBoundingClientRect();
+                container.scrollBy({{
+                    left: nodeRect.left + nodeRect.width / 2 - (containerRect.left + containerRect.width / 2),
+                    top: nodeRect.top + nodeRect.height / 2 - (containerRect.top + containerRect.height / 2),
+                    behavior: "smooth",
+                }});
+                node.classList.add("highlighted");
+                setTimeout(() => node.classList.remove("highlighted"), 1500);
+            }}
+        "#,
+        graph_container_id(target)
+    ));
+    if let Err(err) = scroll.send(node_name) {
+        eprintln!("Could not scroll to node: {err}");
+    }
+}
+
+/// Returns the size available to the graph, padding excluded.
+async fn graph_container_size(target: &Target) -> Option<(f64, f64)> {
+    eval(&format!(
+        r#"
+            const container = document.getElementById("{}");
+            if (!container) {{
+                return null;
+            }}
+            const style = getComputedStyle(container);
+            return [
+                container.clientWidth - parseFloat(style.paddingLeft) - parseFloat(style.paddingRight),
+                container.clientHeight - parseFloat(style.paddingTop) - parseFloat(style.paddingBottom),
+            ];
+        "#,
+        graph_container_id(target)
+    ))
+    .join::<Option<(f64, f64)>>()
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Once the graph has been rescaled by `ratio`, scrolls it so that the point under the pointer stays
+/// in place.
+fn keep_graph_point_under(target: &Target, client_x: f64, client_y: f64, ratio: f64) {
+    eval(&format!(
+        r#"
+            const container = document.getElementById("{}");
+            if (container) {{
+                const rect = container.getBoundingClientRect();
+                const x = {client_x} - rect.left;
+                const y = {client_y} - rect.top;
+                const left = (container.scrollLeft + x) * {ratio} - x;
+                const top = (container.scrollTop + y) * {ratio} - y;
+                requestAnimationFrame(() => container.scrollTo(left, top));
+            }}
+        "#,
+        graph_container_id(target)
+    ));
+}
+
+fn copy_to_clipboard(text: &str) {
+    let copy = eval(
+        r#"
+            await navigator.clipboard.writeText(await dioxus.recv());
+        "#,
+    );
+    if let Err(err) = copy.send(text) {
+        eprintln!("Could not copy to clipboard: {err}");
+    }
+}
+
+/// Asks for the permission to show notifications, which is remembered once granted.
+async fn request_notification_permission() -> bool {
+    let mut permission = eval(
+        r#"
+            dioxus.send(typeof Notification === "undefined" ? "unsupported" : await Notification.requestPermission());
+        "#,
+    );
+    permission
+        .recv::<String>()
+        .await
+        .is_ok_and(|permission| permission == "granted")
+}
 
-    let svg = use_signal(|| None);
-    let svg_size = use_memo(move || {
-        svg().as_ref().map(|svg: &String| {
-            let captures = SVG_SIZE_REGEX.captures(svg).unwrap();
-            (
-                captures[1].parse::<usize>().unwrap(),
-                captures[2].parse::<usize>().unwrap(),
-            )
-        })
-    });
-    let mut scale_percent = use_signal(|| 100);
-    let svg_scaled_size = use_memo(move || {
-        svg_size().map(|(width, height)| {
-            (
-                width * scale_percent() / 100,
-                height * scale_percent() / 100,
-            )
-        })
-    });
+fn notify(title: &str, body: &str) {
+    let notification = eval(
+        r#"
+            const [title, body] = await dioxus.recv();
+            if (typeof Notification !== "undefined" && Notification.permission === "granted") {
+                new Notification(title, { body });
+            }
+        "#,
+    );
+    if let Err(err) = notification.send((title, body)) {
+        eprintln!("Could not notify: {err}");
+    }
+}
 
-    let mut rpc_state = use_signal(|| RpcState::Connecting);
+/// Opens a stream to the teleop server of the target, split into its reading and writing halves.
+async fn open_stream(
+    target: &Target,
+) -> Result<(Box<dyn AsyncRead + Unpin>, Box<dyn AsyncWrite + Unpin>), Box<dyn std::error::Error>> {
+    match target {
+        Target::Pid(pid) => {
+            let (input, output) = connect::<DefaultAttacher>(*pid).await?.split();
+            Ok((Box::new(input), Box::new(output)))
+        }
+        Target::Address(address) => {
+            let (input, output) = TcpStream::connect(address.as_str()).await?.split();
+            Ok((Box::new(input), Box::new(output)))
+        }
+    }
+}
 
-    let state_span = match *rpc_state.read() {
-        RpcState::Connecting => rsx! {
-            div { "aria-label": "status", class: "status status-neutral" }
-        },
-        RpcState::Connected => rsx! {
-            div { "aria-label": "success", class: "status status-success" }
-        },
-        RpcState::Disconnected => rsx! {
-            div { "aria-label": "error", class: "status status-error" }
-        },
+/// Whether the process is still running, be it attachable or not, e.g. because its teleop server
+/// is not ready yet or because it belongs to another user.
+fn process_exists(pid: u32) -> bool {
+    if cfg!(target_os = "linux") {
+        Path::new(&format!("/proc/{pid}")).exists()
+    } else {
+        // Let the connection attempt tell.
+        true
+    }
+}
+
+/// Connects to the process and polls it until the pipeline finishes or the connection drops.
+///
+/// This can be run again on the same session signals in order to reconnect.
+async fn run_session(
+    target: Target,
+    session: TeleopSession,
+    commands: UnboundedReceiver<PollCommand>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let TeleopSession {
+        mut rpc_state,
+        connect_timeout_ms,
+        mut missing_engine,
+        mut layout_timed_out,
+        mut connected_at,
+        mut finished,
+        mut error,
+        mut warning,
+        mut rtt,
+        mut history,
+        ..
+    } = session;
+
+    rpc_state.set(RpcState::Connecting);
+    missing_engine.set(None);
+    layout_timed_out.set(None);
+    connected_at.set(None);
+    finished.set(false);
+    error.set(None);
+    warning.set(None);
+    rtt.set(None);
+    history.set(BTreeMap::new());
+
+    let connect_timeout = Duration::from_millis(*connect_timeout_ms.peek());
+
+    let (input, output) = match with_timeout(connect_timeout, open_stream(&target)).await {
+        Some(Ok(stream)) => stream,
+        Some(Err(err)) => {
+            // The attach errors do not tell whether the process is gone.
+            let reason = match target {
+                Target::Pid(pid) if !process_exists(pid) => PROCESS_EXITED,
+                _ => ATTACH_FAILED,
+            };
+            rpc_state.set(RpcState::Disconnected(Some(reason.to_owned())));
+            error.set(Some(format!("Could not connect: {err}")));
+            return Err(err);
+        }
+        None => {
+            rpc_state.set(RpcState::Disconnected(Some(
+                CONNECTION_TIMED_OUT.to_owned(),
+            )));
+            return Err("connection timed out".into());
+        }
     };
 
-    use_future(move || async move {
-        let stream = connect::<DefaultAttacher>(pid).await?;
+    let (rpc_system, teleop) = client_connection(input, output).await;
+    let rpc_disconnector = rpc_system.get_disconnector();
 
-        rpc_state.set(RpcState::Connected);
+    let polling = async move {
+        let sent_at = Instant::now();
+        let state = match with_timeout(connect_timeout, pipelined_state_service(&teleop)).await {
+            Some(Ok((state, graph, statuses))) => {
+                // Shown like the round trip of a poll, which it is despite the extra request.
+                rtt.set(Some(sent_at.elapsed()));
+                Some(Ok((state, Some((graph, statuses)))))
+            }
+            // The service may not be ready yet, it is then requested again the usual way.
+            Some(Err(err)) => {
+                eprintln!("Could not get the state service along with the graph: {err}");
+                with_timeout(connect_timeout, state_service(&teleop))
+                    .await
+                    .map(|result| result.map(|state| (state, None)))
+            }
+            None => None,
+        };
+        match state {
+            Some(Ok((state, first_frame))) => {
+                rpc_state.set(RpcState::Connected);
+                connected_at.set(Some(Instant::now()));
 
-        let (input, output) = stream.split();
-        let (rpc_system, teleop) = client_connection(input, output).await;
-        let rpc_disconnector = rpc_system.get_disconnector();
+                if let Err(err) = poll(&target, session, state, first_frame, commands).await {
+                    eprintln!("Error in poller: {err}");
+                    error.set(Some(format!("Polling failed: {err}")));
+                }
+                None
+            }
+            Some(Err(err)) => {
+                eprintln!("Could not get the state service: {err}");
+                Some(format!("State service unavailable: {err}"))
+            }
+            None => Some(CONNECTION_TIMED_OUT.to_owned()),
+        }
+    };
 
-        spawn(async move {
-            if let Err(err) = rpc_system.await {
+    // The RPC system is driven by the session itself rather than by a task of its own, so that the
+    // connection cannot outlive the session: when the tab is closed or the session restarted, the
+    // session future is dropped and the connection along with it.
+    let reason = match future::select(pin!(rpc_system), pin!(polling)).await {
+        Either::Left((result, _)) => {
+            if let Err(err) = result {
                 eprintln!("Connection interrupted {err}");
+                error.set(Some(format!("Connection interrupted: {err}")));
+            }
+            None
+        }
+        Either::Right((reason, rpc_system)) => {
+            let _ = future::join(rpc_disconnector, rpc_system).await;
+            reason
+        }
+    };
+
+    rpc_state.set(RpcState::Disconnected(reason));
+
+    Ok(())
+}
+
+fn is_connection_error(err: &(dyn std::error::Error + 'static)) -> bool {
+    err.downcast_ref::<capnp::Error>()
+        .is_some_and(|err| err.kind == capnp::ErrorKind::Disconnected)
+}
+
+/// Runs `future` unless it takes longer than `timeout`.
+async fn with_timeout<T>(timeout: Duration, future: impl Future<Output = T>) -> Option<T> {
+    match future::select(pin!(future), Timer::after(timeout)).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}
+
+const STATE_SERVICE_ATTEMPTS: usize = 5;
+
+const STATE_SERVICE_RETRY_DELAY_MS: u64 = 500;
+
+/// Gets the state service, retrying a few times since it may not be registered yet by a process that
+/// is still starting up.
+async fn state_service(
+    teleop: &Client,
+) -> Result<quirky_binder_capnp::state::Client, Box<dyn std::error::Error>> {
+    let mut attempt = 1;
+    loop {
+        match request_state_service(teleop).await {
+            Ok(state) => return Ok(state),
+            Err(err) if attempt < STATE_SERVICE_ATTEMPTS => {
+                eprintln!("Could not get the state service (attempt {attempt}): {err}");
+                attempt += 1;
+                Timer::after(Duration::from_millis(STATE_SERVICE_RETRY_DELAY_MS)).await;
             }
-        });
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Requests the state service along with the graph and the statuses of its nodes, without waiting
+/// for the service to be answered: capnp pipelines the calls made on the promised service, so that
+/// the first graph takes a single round trip.
+async fn pipelined_state_service(
+    teleop: &Client,
+) -> Result<
+    (
+        quirky_binder_capnp::state::Client,
+        PipelineGraph,
+        BTreeMap<String, NodeDetails>,
+    ),
+    Box<dyn std::error::Error>,
+> {
+    let mut req = teleop.service_request();
+    req.get().set_name("state");
+    let response = req.send();
+    let state: quirky_binder_capnp::state::Client =
+        capnp::capability::FromClientHook::new(response.pipeline.get_service().as_cap());
+    let (service, first_frame) = future::join(
+        response.promise,
+        future::try_join(fetch_graph(&state), fetch_statuses(&state)),
+    )
+    .await;
+    service?;
+    let (graph, statuses) = first_frame?;
+    Ok((state, graph, statuses))
+}
+
+async fn request_state_service(
+    teleop: &Client,
+) -> Result<quirky_binder_capnp::state::Client, Box<dyn std::error::Error>> {
+    let mut req = teleop.service_request();
+    req.get().set_name("state");
+    let state = req.send().promise.await?;
+    let state = state.get()?.get_service();
+    Ok(state.get_as()?)
+}
+
+fn node_state(
+    state: quirky_binder_capnp::node_state::Reader,
+) -> Result<NodeState, Box<dyn std::error::Error>> {
+    Ok(match state.which()? {
+        quirky_binder_capnp::node_state::Which::Waiting(()) => NodeState::Waiting,
+        quirky_binder_capnp::node_state::Which::Running(()) => NodeState::Running,
+        quirky_binder_capnp::node_state::Which::Success(()) => NodeState::Success,
+        quirky_binder_capnp::node_state::Which::Error(error) => {
+            NodeState::Error(error?.to_str()?.to_owned())
+        }
+    })
+}
 
-        if let Err(err) = poll(theme, teleop, svg).await {
-            eprintln!("Error in poller: {err}");
+/// Briefly connects to the process in order to run `f` with its teleop client.
+async fn with_connection<T>(
+    pid: u32,
+    f: impl AsyncFnOnce(Client) -> Result<T, Box<dyn std::error::Error>>,
+) -> Result<T, Box<dyn std::error::Error>> {
+    let stream = with_timeout(
+        Duration::from_millis(DEFAULT_CONNECT_TIMEOUT_MS),
+        connect::<DefaultAttacher>(pid),
+    )
+    .await
+    .ok_or(CONNECTION_TIMED_OUT)??;
+    let (input, output) = stream.split();
+    let (rpc_system, teleop) = client_connection(input, output).await;
+    let rpc_disconnector = rpc_system.get_disconnector();
+
+    // As in `run_session`, the connection is dropped along with the future.
+    match future::select(pin!(rpc_system), pin!(f(teleop))).await {
+        Either::Left((result, _)) => {
+            result?;
+            Err("connection closed".into())
+        }
+        Either::Right((output, rpc_system)) => {
+            let _ = future::join(rpc_disconnector, rpc_system).await;
+            output
         }
+    }
+}
 
-        let _ = rpc_disconnector.await;
+/// Briefly connects to the process to get the worst state of its nodes, if it has any.
+async fn probe_worst_state(pid: u32) -> Result<Option<NodeState>, Box<dyn std::error::Error>> {
+    with_connection(pid, async |teleop| {
+        let state = with_timeout(
+            Duration::from_millis(DEFAULT_CONNECT_TIMEOUT_MS),
+            request_state_service(&teleop),
+        )
+        .await
+        .ok_or(CONNECTION_TIMED_OUT)??;
+        Ok(fetch_statuses(&state)
+            .await?
+            .into_values()
+            .map(|details| details.state)
+            .max_by_key(NodeState::severity))
+    })
+    .await
+}
 
-        rpc_state.set(RpcState::Disconnected);
+/// Briefly connects to the process to get the graph of its pipeline.
+async fn fetch_process_graph(pid: u32) -> Result<PipelineGraph, Box<dyn std::error::Error>> {
+    with_connection(pid, async |teleop| {
+        let state = with_timeout(
+            Duration::from_millis(DEFAULT_CONNECT_TIMEOUT_MS),
+            state_service(&teleop),
+        )
+        .await
+        .ok_or(CONNECTION_TIMED_OUT)??;
+        fetch_graph(&state).await
+    })
+    .await
+}
 
-        Ok::<_, Box<dyn std::error::Error>>(())
-    });
+/// Renders the graph of the process once to `out`, as PNG if its extension says so or as SVG
+/// otherwise.
+async fn render(
+    pid: u32,
+    engine: GraphEngine,
+    out: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let dot = with_connection(pid, async |teleop| {
+        let state = with_timeout(
+            Duration::from_millis(DEFAULT_CONNECT_TIMEOUT_MS),
+            state_service(&teleop),
+        )
+        .await
+        .ok_or(CONNECTION_TIMED_OUT)??;
+        let graph = fetch_graph(&state).await?;
+        let statuses = fetch_statuses(&state).await?;
+        let frame = build_dot(
+            &graph,
+            &statuses,
+            &GraphOptions::default(),
+            &mut BTreeMap::new(),
+            &mut None,
+        )?;
+        Ok(frame.dot)
+    })
+    .await?;
 
-    let nav = navigator();
+    let timeout = Duration::from_millis(DEFAULT_LAYOUT_TIMEOUT_MS);
+    if out.extension().is_some_and(|extension| extension == "png") {
+        smol::fs::write(out, dot_to_png(engine, &dot, timeout).await?).await?;
+    } else {
+        smol::fs::write(out, dot_to_svg(engine, &dot, timeout).await?).await?;
+    }
+    Ok(())
+}
+
+/// Meaning of the node colors, as rendered by `poll`.
+///
+/// Dashed nodes are running but did not process any record yet, or their status is unknown.
+const NODE_LEGEND: [(NodeState, bool, &str); 6] = [
+    (NodeState::Waiting, false, "Waiting"),
+    (NodeState::Running, true, "Running, idle"),
+    (NodeState::Running, false, "Running"),
+    (NodeState::Success, false, "Success"),
+    (NodeState::Error(String::new()), false, "Error"),
+    (NodeState::Unknown, true, "Unknown"),
+];
+
+fn legend_swatch_style(state: &NodeState, dashed: bool, palette: &Palette) -> String {
+    let color = state.color(palette);
+    match state.css_border().filter(|_| palette.distinct_borders) {
+        _ if dashed => format!("border: 2px dashed {color};"),
+        Some(border) => format!(
+            "background-color: {color}; border: 3px {border} {};",
+            palette.foreground
+        ),
+        None => format!("background-color: {color};"),
+    }
+}
+
+#[component]
+fn GraphLegend(
+    edge_thresholds: EdgeThresholds,
+    palette: Palette,
+    ondismiss: EventHandler,
+) -> Element {
+    let mut expanded = use_signal(|| true);
+
+    let EdgeThresholds { busy, backed_up } = edge_thresholds;
 
     rsx! {
         div {
-            class: "teleop",
-
+            class: "teleop-legend card card-sm bg-base-100 shadow-sm",
             div {
-                class: "breadcrumbs",
+                class: "card-body",
+                div {
+                    class: "card-title",
+                    button {
+                        class: "btn btn-xs btn-ghost",
+                        onclick: move |_| expanded.set(!expanded()),
+                        if expanded() { "▾ Legend" } else { "▸ Legend" }
+                    }
+                    button {
+                        class: "btn btn-xs btn-ghost",
+                        "aria-label": "Dismiss",
+                        title: "Dismiss, it can be shown again from the settings",
+                        onclick: move |_| ondismiss.call(()),
+                        "✕"
+                    }
+                }
+                if expanded() {
+                    table {
+                        class: "table table-xs",
+                        tbody {
+                            for (state, dashed, label) in NODE_LEGEND {
+                                tr {
+                                    key: "{label}",
+                                    td {
+                                        span {
+                                            class: "teleop-legend-swatch",
+                                            style: legend_swatch_style(&state, dashed, &palette),
+                                        }
+                                    }
+                                    td { "{label}" }
+                                }
+                            }
+                            tr {
+                                td { span { class: "teleop-legend-line", style: "background-color: {palette.good};" } }
+                                td { "Fewer than {busy} records in flight" }
+                            }
+                            tr {
+                                td { span { class: "teleop-legend-line", style: "background-color: {palette.busy};" } }
+                                td { "From {busy} records in flight" }
+                            }
+                            tr {
+                                td { span { class: "teleop-legend-line", style: "background-color: {palette.bad};" } }
+                                td { "From {backed_up} records in flight" }
+                            }
+                            tr {
+                                td { span { class: "teleop-legend-line", style: "height: 5px; background-color: {palette.foreground};" } }
+                                td { "Records moved since the previous poll" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
 
-                ul {
-                    li { a {
-                        onclick: move |_| { nav.push(Route::Home {}); },
-                        "Home"
-                    } }
-                    li {
-                        span { "Process {pid}" {state_span} }
+/// Whole graph at a small scale, with the part visible in the graph container outlined.
+///
+/// Clicking the minimap centers the graph container on the clicked point.
+#[component]
+fn Minimap(target: Target, src: String) -> Element {
+    let target = use_signal(|| target);
+    // Visible part of the graph, as fractions of its scrollable size: left, top, width, height.
+    let mut viewport = use_signal(|| None::<(f64, f64, f64, f64)>);
+
+    // The graph container outlives the minimap, e.g. when the text view is toggled.
+    use_drop(move || {
+        eval(&format!(
+            r#"document.getElementById("{}")?.minimapUnbind?.();"#,
+            graph_container_id(&target.peek()),
+        ));
+    });
+
+    rsx! {
+        div {
+            id: minimap_id(&target()),
+            class: "teleop-minimap card bg-base-100 shadow-sm",
+            onmounted: move |_| async move {
+                let mut updates = eval(&format!(
+                    r#"
+                        const container = document.getElementById("{}");
+                        const minimap = document.getElementById("{}");
+                        if (container && minimap) {{
+                            // Listeners of a previous mount would send to a closed channel.
+                            container.minimapUnbind?.();
+                            const listeners = new AbortController();
+                            const update = () => dioxus.send([
+                                container.scrollLeft / container.scrollWidth,
+                                container.scrollTop / container.scrollHeight,
+                                Math.min(container.clientWidth / container.scrollWidth, 1),
+                                Math.min(container.clientHeight / container.scrollHeight, 1),
+                            ]);
+                            container.addEventListener("scroll", update, {{ signal: listeners.signal }});
+                            const observer = new ResizeObserver(update);
+                            observer.observe(container);
+                            for (const child of container.children) {{
+                                observer.observe(child);
+                            }}
+                            container.minimapUnbind = () => {{
+                                listeners.abort();
+                                observer.disconnect();
+                                delete container.minimapUnbind;
+                            }};
+                            minimap.addEventListener("click", (event) => {{
+                                const rect = minimap.getBoundingClientRect();
+                                const x = (event.clientX - rect.left) / rect.width;
+                                const y = (event.clientY - rect.top) / rect.height;
+                                container.scrollTo({{
+                                    left: x * container.scrollWidth - container.clientWidth / 2,
+                                    top: y * container.scrollHeight - container.clientHeight / 2,
+                                    behavior: "smooth",
+                                }});
+                            }}, {{ signal: listeners.signal }});
+                            update();
+                        }}
+                    "#,
+                    graph_container_id(&target()),
+                    minimap_id(&target()),
+                ));
+                while let Ok(update) = updates.recv::<(f64, f64, f64, f64)>().await {
+                    viewport.set(Some(update));
+                }
+            },
+            img { src, alt: "" }
+            if let Some((left, top, width, height)) = viewport() {
+                div {
+                    class: "teleop-minimap-viewport",
+                    style: "left: {left * 100.0}%; top: {top * 100.0}%; width: {width * 100.0}%; height: {height * 100.0}%;",
+                }
+            }
+        }
+    }
+}
+
+/// Nodes and edges of the pipeline as tables, a dense view which does not need Graphviz.
+#[component]
+fn StatusTables(snapshot: GraphSnapshot, onselect: EventHandler<String>) -> Element {
+    rsx! {
+        div {
+            class: "teleop-tables",
+            table {
+                class: "table table-xs table-pin-rows",
+                thead {
+                    tr {
+                        th { "Node" }
+                        th { "State" }
+                        th { class: "text-right", "Read" }
+                        th { class: "text-right", "Written" }
+                    }
+                }
+                tbody {
+                    for node in snapshot.nodes {
+                        tr {
+                            key: "{node.name}",
+                            class: "hover cursor-pointer",
+                            onclick: {
+                                let name = node.name.clone();
+                                move |_| onselect.call(name.clone())
+                            },
+                            td { "{node.name}" }
+                            td {
+                                title: node.error.clone().unwrap_or_default(),
+                                "{node.state}"
+                            }
+                            td { class: "text-right teleop-elapsed", "{node.input_read.iter().sum::<u64>()}" }
+                            td { class: "text-right teleop-elapsed", "{node.output_written.iter().sum::<u64>()}" }
+                        }
                     }
                 }
             }
-            div {
-                class: "teleop-svg",
-                if let Some((width, height)) = svg_scaled_size() {
-                    style {
-                        r#"
-                            .teleop-svg > div > svg {{ width: {width}px; height: {height}px; }}
-                        "#
-                    },
+            table {
+                class: "table table-xs table-pin-rows",
+                thead {
+                    tr {
+                        th { "Edge" }
+                        th { class: "text-right", "Written" }
+                        th { class: "text-right", "Read" }
+                        th { class: "text-right", "In flight" }
+                    }
                 }
-                if let Some(svg) = svg() {
-                    div {
-                        dangerous_inner_html: "{svg}",
+                tbody {
+                    for edge in snapshot.edges {
+                        tr {
+                            key: "{edge.tail_name}-{edge.tail_index}-{edge.head_name}-{edge.head_index}",
+                            td { "{edge.tail_name} out[{edge.tail_index}] → {edge.head_name} in[{edge.head_index}]" }
+                            td { class: "text-right teleop-elapsed", {edge.written.map(|n| n.to_string()).unwrap_or_default()} }
+                            td { class: "text-right teleop-elapsed", {edge.read.map(|n| n.to_string()).unwrap_or_default()} }
+                            td {
+                                class: "text-right teleop-elapsed",
+                                {edge.written.zip(edge.read).map(|(written, read)| (written as i64 - read as i64).to_string()).unwrap_or_default()}
+                            }
+                        }
                     }
                 }
             }
+        }
+    }
+}
+
+#[component]
+fn NodeDetailsPanel(
+    node_name: String,
+    details: NodeDetails,
+    note: String,
+    focused: bool,
+    onclose: EventHandler,
+    onfocus: EventHandler,
+    onnote: EventHandler<String>,
+) -> Element {
+    // Inputs lagging behind the most advanced one are pointed out.
+    let max_read = details.input_read.iter().copied().max().unwrap_or(0);
+
+    rsx! {
+        div {
+            class: "teleop-details card card-sm bg-base-100 shadow-sm",
             div {
-                class: "teleop-footer",
-                input {
-                    type: "range",
-                    min: 10,
-                    max: 200,
-                    step: 10,
-                    value: scale_percent(),
-                    class: "range range-primary",
-                    oninput: move |e| {
-                        if let Ok(value) = e.value().parse() {
-                            scale_percent.set(value);
-                        }
-                    },
+                class: "card-body",
+                div {
+                    class: "card-title",
+                    span { class: "teleop-details-name", "{node_name}" }
+                    button {
+                        class: "btn btn-xs btn-ghost",
+                        "aria-label": "Close",
+                        onclick: move |_| onclose.call(()),
+                        "✕"
+                    }
                 }
-                /*
-                input {
-                    type: "checkbox",
-                    value: "dark",
-                    class: "toggle",
-                    onchange: move |e| {
-                        match e.checked() {
-                            false => { theme.set(AppTheme::Light); }
-                            true => { theme.set(AppTheme::Dark); }
+                div {
+                    class: "flex items-center justify-between",
+                    span { class: "badge badge-sm", "{details.state.label()}" }
+                    button {
+                        class: if focused { "btn btn-xs btn-active" } else { "btn btn-xs" },
+                        title: "Show only this node and its direct neighbors",
+                        onclick: move |_| onfocus.call(()),
+                        if focused { "Unfocus" } else { "Focus" }
+                    }
+                }
+                if let NodeState::Error(error) = &details.state {
+                    div { class: "text-error", "{error}" }
+                }
+                table {
+                    class: "table table-xs",
+                    thead {
+                        tr {
+                            th { "Port" }
+                            th { "Records" }
+                        }
+                    }
+                    tbody {
+                        for (index, read) in details.input_read.iter().enumerate() {
+                            tr {
+                                key: "in-{index}",
+                                td { "in[{index}]" }
+                                td {
+                                    "{read} read"
+                                    if *read < max_read {
+                                        span {
+                                            class: "badge badge-xs badge-warning badge-soft ml-1",
+                                            title: "Behind the most advanced input",
+                                            "-{max_read - read}"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        for (index, written) in details.output_written.iter().enumerate() {
+                            tr {
+                                key: "out-{index}",
+                                td { "out[{index}]" }
+                                td { "{written} written" }
+                            }
                         }
                     }
                 }
-                */
+                textarea {
+                    class: "textarea textarea-sm",
+                    placeholder: "Note pinned next to the node",
+                    value: "{note}",
+                    onchange: move |e| onnote.call(e.value()),
+                }
             }
         }
     }
 }
 
-pub fn node_name_to_dot_id(name: &str) -> String {
-    format!("\"{name}\"")
+fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
 }
 
-pub async fn dot_to_svg(dot_source: &str) -> std::io::Result<String> {
-    let mut child = Command::new("dot")
-        .arg("-Tsvg")
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .spawn()?;
+/// State of the pipeline as of a poll, in a form meant for other tools.
+#[derive(Clone, PartialEq, Serialize)]
+struct GraphSnapshot {
+    nodes: Vec<NodeSnapshot>,
+    edges: Vec<EdgeSnapshot>,
+}
 
-    if let Some(mut stdin) = child.stdin.take() {
-        stdin.write_all(dot_source.as_bytes()).await?;
-    }
+#[derive(Clone, PartialEq, Serialize)]
+struct NodeSnapshot {
+    name: String,
+    state: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    input_read: Vec<u64>,
+    output_written: Vec<u64>,
+}
 
-    let output = child.output().await?;
+#[derive(Clone, PartialEq, Serialize)]
+struct EdgeSnapshot {
+    tail_name: String,
+    tail_index: usize,
+    head_name: String,
+    head_index: usize,
+    /// Records written by the tail node, if known.
+    written: Option<u64>,
+    /// Records read by the head node, if known.
+    read: Option<u64>,
+}
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        let error_message = String::from_utf8_lossy(&output.stderr);
-        Err(std::io::Error::other(format!(
-            "Erreur lors de l'exécution de la commande dot : {error_message}"
-        )))
+impl GraphSnapshot {
+    fn new(graph: &PipelineGraph, statuses: &BTreeMap<String, NodeDetails>) -> Self {
+        let nodes = graph
+            .nodes
+            .iter()
+            .map(|node_name| {
+                let details = statuses.get(node_name);
+                let state = details.map_or(NodeState::Unknown, |details| details.state.clone());
+                NodeSnapshot {
+                    name: node_name.clone(),
+                    state: state.label(),
+                    error: match state {
+                        NodeState::Error(error) => Some(error),
+                        _ => None,
+                    },
+                    input_read: details
+                        .map(|details| details.input_read.clone())
+                        .unwrap_or_default(),
+                    output_written: details
+                        .map(|details| details.output_written.clone())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+        let edges = graph
+            .edges
+            .iter()
+            .map(
+                |(tail_name, tail_index, head_name, head_index)| EdgeSnapshot {
+                    tail_name: tail_name.clone(),
+                    tail_index: *tail_index,
+                    head_name: head_name.clone(),
+                    head_index: *head_index,
+                    written: statuses
+                        .get(tail_name)
+                        .and_then(|details| details.output_written.get(*tail_index).copied()),
+                    read: statuses
+                        .get(head_name)
+                        .and_then(|details| details.input_read.get(*head_index).copied()),
+                },
+            )
+            .collect();
+        Self { nodes, edges }
     }
 }
 
-const GREY: &str = "#59636e";
-const GREEN: &str = "#1a7f37";
-const ORANGE: &str = "#dbab0a";
-const RED: &str = "#d1242f";
+async fn fetch_graph(
+    state: &quirky_binder_capnp::state::Client,
+) -> Result<PipelineGraph, Box<dyn std::error::Error>> {
+    let graph = state.graph_request().send().promise.await?;
+    let graph = graph.get()?.get_graph()?;
+
+    // The nodes and edges may not come in the same order from one process run to another, sorting
+    // them keeps the layout from changing for the same pipeline.
+    let mut nodes = graph
+        .get_nodes()?
+        .into_iter()
+        .map(|node| Ok(node.get_name()?.to_str()?.to_owned()))
+        .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+    nodes.sort_unstable();
+    let mut edges = graph
+        .get_edges()?
+        .into_iter()
+        .map(|edge| {
+            Ok((
+                edge.get_tail_name()?.to_str()?.to_owned(),
+                edge.get_tail_index() as usize,
+                edge.get_head_name()?.to_str()?.to_owned(),
+                edge.get_head_index() as usize,
+            ))
+        })
+        .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+    edges.sort_unstable();
+
+    Ok(PipelineGraph { nodes, edges })
+}
+
+/// Returns the status of every node which reported one.
+async fn fetch_statuses(
+    state: &quirky_binder_capnp::state::Client,
+) -> Result<BTreeMap<String, NodeDetails>, Box<dyn std::error::Error>> {
+    let statuses = state.node_statuses_request().send().promise.await?;
+    statuses
+        .get()?
+        .get_statuses()?
+        .into_iter()
+        .map(|status| {
+            Ok((
+                status.get_node_name()?.to_str()?.to_owned(),
+                NodeDetails {
+                    state: node_state(status.get_state()?)?,
+                    input_read: status.get_input_read()?.iter().map(u64::from).collect(),
+                    output_written: status.get_output_written()?.iter().map(u64::from).collect(),
+                },
+            ))
+        })
+        .collect()
+}
 
 async fn poll(
-    theme: Signal<AppTheme>,
-    teleop: Client,
-    mut svg: Signal<Option<String>>,
+    target: &Target,
+    session: TeleopSession,
+    state: quirky_binder_capnp::state::Client,
+    // Graph and statuses fetched along with the state service, if so.
+    mut first_frame: Option<(PipelineGraph, BTreeMap<String, NodeDetails>)>,
+    mut commands: UnboundedReceiver<PollCommand>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut req = teleop.service_request();
-    req.get().set_name("state");
-    let state = req.send().promise.await?;
-    let state = state.get()?.get_service();
-    let state: quirky_binder_capnp::state::Client = state.get_as()?;
+    let TeleopSession {
+        theme,
+        color_blind,
+        poll_interval_ms,
+        adaptive_polling,
+        mut poll_delay_ms,
+        paused,
+        engine,
+        layout_timeout_ms,
+        rank_dir,
+        edge_thresholds,
+        label_templates,
+        dot_style,
+        mut missing_engine,
+        mut layout_timed_out,
+        dot: mut dot_state,
+        mut svg,
+        nodes: mut nodes_state,
+        mut finished,
+        mut rtt,
+        mut graph_size,
+        mut snapshot,
+        mut backlog_hint,
+        mut backlog_explained,
+        text_view,
+        cluster_delimiter,
+        collapsed_clusters,
+        mut history,
+        record_dir,
+        mut recording,
+        mut recorded_frames,
+        mut error,
+        mut warning,
+        mut updated_at,
+        show_counters,
+        mut mark,
+        show_percentages,
+        show_critical_path,
+        expected_records,
+        notify_errors,
+        notes,
+        focus,
+        ..
+    } = session;
 
-    let graph = state.graph_request().send().promise.await?;
-    let graph = graph.get()?.get_graph()?;
+    let mut pipeline = None;
 
-    let mut update_graph = async || -> Result<bool, Box<dyn std::error::Error>> {
-        let statuses = state.node_statuses_request().send().promise.await?;
-        let statuses = statuses.get()?.get_statuses()?;
-        let statuses = statuses
-            .into_iter()
-            .map(|s| Ok((s.get_node_name()?.to_str()?, s)))
-            .collect::<capnp::Result<BTreeMap<&str, _>>>()?;
+    // Records read through each edge as of the previous poll, in order to compute throughputs.
+    let mut previous_head_counters = None;
 
-        let mut dot = String::new();
+    // Hash of the engine and DOT source behind the current SVG, so that an unchanged graph is not
+    // rendered again, which would make it flicker.
+    let mut rendered_hash: Option<u64> = None;
 
-        writeln!(&mut dot, "digraph G {{")?;
+    // Layout which took longer than the timeout, so that the engine is not run again on the same
+    // graph at every poll, see `layout_hash`.
+    let mut timed_out_hash: Option<u64> = None;
 
-        writeln!(&mut dot, "    graph [bgcolor=\"transparent\"];")?;
+    // Copied as `update_graph` borrows the signal itself.
+    let nodes_read = nodes_state;
 
-        match *theme.read() {
-            AppTheme::Light | AppTheme::Corporate => {
-                writeln!(&mut dot, "    node [fontcolor=\"black\", color=\"black\"];")?;
-                writeln!(&mut dot, "    edge [fontcolor=\"black\", color=\"black\"];")?;
-            }
-            AppTheme::Dark => {
-                writeln!(&mut dot, "    node [fontcolor=\"white\", color=\"white\"];")?;
-                writeln!(&mut dot, "    edge [fontcolor=\"white\", color=\"white\"];")?;
+    let mut update_graph = async || -> Result<bool, Box<dyn std::error::Error>> {
+        let (graph, statuses) = match first_frame.take() {
+            Some(frame) => frame,
+            None => {
+                let sent_at = Instant::now();
+                // The graph is fetched again after a failed poll, in case the process restarted,
+                // and then along with the statuses rather than one after the other.
+                let frame = match pipeline.take() {
+                    Some(graph) => (graph, fetch_statuses(&state).await?),
+                    None => future::try_join(fetch_graph(&state), fetch_statuses(&state)).await?,
+                };
+                rtt.set(Some(sent_at.elapsed()));
+                frame
             }
+        };
+        graph_size.set(Some((graph.nodes.len(), graph.edges.len())));
+
+        // Counters going backwards mean that the process restarted, what was tracked from the
+        // previous run is meaningless then.
+        if counters_went_backwards(&nodes_state.peek(), &statuses) {
+            previous_head_counters = None;
+            history.set(BTreeMap::new());
+            mark.set(None);
+            error.set(Some(
+                "Counters reset — the process may have restarted".to_owned(),
+            ));
         }
 
-        let mut finished = true;
+        let options = GraphOptions {
+            palette: theme().palette(color_blind()),
+            rank_dir: rank_dir(),
+            dot_style: dot_style(),
+            edge_thresholds: edge_thresholds(),
+            label_templates: label_templates(),
+            cluster_delimiter: cluster_delimiter(),
+            collapsed_clusters: collapsed_clusters(),
+            show_counters: show_counters(),
+            expected_records: expected_records().filter(|_| show_percentages()),
+            critical_path: show_critical_path(),
+            mark: mark(),
+            notes: notes(),
+            focus: focus(),
+        };
+        let mut nodes_history = history();
+        let GraphFrame {
+            dot,
+            nodes: nodes_details,
+            finished,
+            failed_nodes,
+            backed_up,
+        } = build_dot(
+            &graph,
+            &statuses,
+            &options,
+            &mut nodes_history,
+            &mut previous_head_counters,
+        )?;
+        history.set(nodes_history);
+        snapshot.set(Some(GraphSnapshot::new(&graph, &statuses)));
 
-        let nodes = graph.get_nodes()?;
+        if backed_up && !backlog_explained() {
+            backlog_explained.set(true);
+            backlog_hint.set(Some(options.edge_thresholds.backed_up));
+        }
 
-        for node in nodes {
-            let node_name = node.get_name()?.to_str()?;
-
-            write!(&mut dot, "{} [", node_name_to_dot_id(node_name))?;
-
-            let node_status = statuses[node_name];
-            let state = node_status.get_state()?.which()?;
-            let read_records = node_status
-                .get_input_read()?
-                .iter()
-                .fold(None, |acc, read| {
-                    acc.map_or(Some(read), |acc| Some(acc + read))
-                });
-            let written_records = node_status
-                .get_output_written()?
-                .iter()
-                .fold(None, |acc, written| {
-                    acc.map_or(Some(written), |acc| Some(acc + written))
-                });
-            let total_records = read_records.map_or(written_records, |read| {
-                Some(read + written_records.unwrap_or(0))
-            });
+        if notify_errors() && !failed_nodes.is_empty() {
+            notify(
+                &format!("Pipeline failure: {}", target.title()),
+                &format!("Failed: {}", failed_nodes.join(", ")),
+            );
+        }
 
-            for (i, (attr, val)) in [(
-                "color",
-                match state {
-                    quirky_binder_capnp::node_state::Which::Waiting(()) => {
-                        finished = false;
-                        GREY
-                    }
-                    quirky_binder_capnp::node_state::Which::Running(()) => {
-                        finished = false;
-                        match total_records {
-                            None => GREY,
-                            Some(_) => ORANGE,
-                        }
-                    }
-                    quirky_binder_capnp::node_state::Which::Success(()) => GREEN,
-                    quirky_binder_capnp::node_state::Which::Error(_) => RED,
-                },
-            )]
-            .into_iter()
-            .enumerate()
-            {
-                if i > 0 {
-                    write!(&mut dot, ", ")?;
-                } else {
-                    writeln!(&mut dot)?;
-                }
-                writeln!(&mut dot, "{attr} = \"{val}\"",)?;
-            }
-
-            writeln!(&mut dot, "]")?;
-        }
-
-        let edges = graph.get_edges()?;
-
-        for edge in edges {
-            let tail_name = edge.get_tail_name()?.to_str()?;
-            let head_name = edge.get_head_name()?.to_str()?;
-
-            write!(
-                &mut dot,
-                "{} -> {} [",
-                node_name_to_dot_id(tail_name),
-                node_name_to_dot_id(head_name)
-            )?;
-
-            let tail_index = edge.get_tail_index();
-            let tail_counter = statuses
-                .get(tail_name)
-                .map(|s| capnp::Result::Ok(s.get_output_written()?.get(tail_index as _)))
-                .transpose()?;
-
-            let head_index = edge.get_head_index();
-            let head_counter = statuses
-                .get(head_name)
-                .map(|s| capnp::Result::Ok(s.get_input_read()?.get(head_index as _)))
-                .transpose()?;
-
-            let diff_counter = tail_counter.and_then(|t| head_counter.map(|h| t as i32 - h as i32));
-
-            for (i, (attr, val)) in tail_counter
-                .map(|n| ("taillabel", n.to_string()))
-                .into_iter()
-                .chain(head_counter.map(|n| {
-                    (
-                        "headlabel",
-                        if let Some(d) = diff_counter {
-                            format!("{} ({})", n, d)
-                        } else {
-                            format!("{}", n)
-                        },
-                    )
-                }))
-                .chain(diff_counter.map(|d| {
-                    if d < 10 {
-                        ("color", GREEN.to_owned())
-                    } else if d < 42 {
-                        ("color", ORANGE.to_owned())
-                    } else {
-                        ("color", RED.to_owned())
-                    }
-                }))
-                .enumerate()
-            {
-                if i > 0 {
-                    write!(&mut dot, ", ")?;
-                } else {
-                    writeln!(&mut dot)?;
+        // A missing engine or a layout taking too long are reported but do not end the session so
+        // that another engine or timeout can be selected.
+        let engine = engine();
+        let timeout = Duration::from_millis(layout_timeout_ms());
+        let hash = {
+            let mut hasher = DefaultHasher::new();
+            (engine, &dot).hash(&mut hasher);
+            hasher.finish()
+        };
+        let layout_hash = layout_hash(engine, timeout, &graph, &options);
+        if !text_view() && rendered_hash != Some(hash) && timed_out_hash != Some(layout_hash) {
+            match dot_to_svg(engine, &dot, timeout).await {
+                Ok(svg_str) => {
+                    missing_engine.set(None);
+                    layout_timed_out.set(None);
+                    svg.set(Some(svg_str));
+                    rendered_hash = Some(hash);
+                    timed_out_hash = None;
+                }
+                Err(DotError::NotInstalled(engine)) => {
+                    missing_engine.set(Some(engine));
+                    rendered_hash = None;
+                }
+                Err(DotError::TimedOut(engine, timeout)) => {
+                    layout_timed_out.set(Some((engine, timeout)));
+                    timed_out_hash = Some(layout_hash);
                 }
-                writeln!(&mut dot, "{attr} = \"{val}\"",)?;
+                Err(err) => return Err(err.into()),
             }
-
-            writeln!(&mut dot, "]")?;
         }
-        writeln!(&mut dot, "}}")?;
-
-        //println!("DOT: {dot}");
+        // A graph which did not render, e.g. because its layout timed out, leaves the SVG stale.
+        if rendered_hash == Some(hash) || text_view() {
+            updated_at.set(Some(Instant::now()));
+        }
 
-        let svg_str = dot_to_svg(&dot).await?;
+        if recording() && !text_view() && missing_engine.peek().is_none() {
+            if let Some(svg_str) = svg() {
+                let frame = recorded_frames();
+                let path = Path::new(&record_dir()).join(format!(
+                    "quirky-binder-graph-{}-{frame:06}.svg",
+                    target.key()
+                ));
+                match smol::fs::write(&path, svg_str).await {
+                    Ok(()) => recorded_frames.set(frame + 1),
+                    Err(err) => {
+                        recording.set(false);
+                        error.set(Some(format!(
+                            "Recording stopped, could not write {}: {err}",
+                            path.display()
+                        )));
+                    }
+                }
+            }
+        }
 
-        svg.set(Some(svg_str));
+        dot_state.set(Some(dot));
+        nodes_state.set(nodes_details);
 
-        if !finished {
-            Timer::after(Duration::from_millis(3000)).await;
+        // The graph is fetched again until the pipeline shows up.
+        if !graph.nodes.is_empty() {
+            pipeline = Some(graph);
         }
 
         Ok(finished)
     };
 
-    while !update_graph().await? {}
+    // While paused, the connection is kept alive but the graph is left untouched unless a refresh
+    // is explicitly requested.
+    let mut refresh = false;
+    // Records read by all the nodes as of the previous poll, which tells whether records flow.
+    let mut previous_read = None;
+    let mut delay_ms = poll_interval_ms();
+    loop {
+        if refresh || !paused() {
+            match update_graph().await {
+                Ok(true) => {
+                    finished.set(true);
+                    break;
+                }
+                Ok(false) => warning.set(None),
+                // The process may be answering nonsense for a while, e.g. when restarting, the
+                // latest graph is then kept until a poll succeeds.
+                Err(err) if !is_connection_error(&*err) => {
+                    eprintln!("Could not update the graph: {err}");
+                    warning.set(Some(err.to_string()));
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        // Refresh requests received while updating are already satisfied.
+        while let Ok(Some(_)) = commands.try_next() {}
+
+        let interval_ms = poll_interval_ms();
+        let read = nodes_read
+            .peek()
+            .values()
+            .flat_map(|details| &details.input_read)
+            .sum::<u64>();
+        delay_ms = if adaptive_polling() {
+            let min = (interval_ms / ADAPTIVE_POLL_MIN_DIVISOR)
+                .max(MIN_POLL_INTERVAL_MS)
+                .min(interval_ms);
+            let max = interval_ms * ADAPTIVE_POLL_MAX_FACTOR;
+            match previous_read {
+                Some(previous) if read > previous => delay_ms / 2,
+                Some(_) => delay_ms * 2,
+                None => interval_ms,
+            }
+            .clamp(min, max)
+        } else {
+            interval_ms
+        };
+        previous_read = Some(read);
+        poll_delay_ms.set(delay_ms);
+
+        let timer = Timer::after(Duration::from_millis(delay_ms));
+        refresh = match future::select(timer, commands.next()).await {
+            Either::Left(_) => false,
+            Either::Right((Some(PollCommand::Refresh), _)) => true,
+            Either::Right((None, _)) => break,
+        };
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shell_quote_single_quotes() {
+        assert_eq!(shell_quote("/opt/console/bin"), "'/opt/console/bin'");
+        assert_eq!(shell_quote("/home/o'brien/bin"), r"'/home/o'\''brien/bin'");
+    }
+
+    #[test]
+    fn parse_svg_size_in_points() {
+        let svg = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN"
+ "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd">
+<!-- Generated by graphviz version 2.43.0 (0)
+ -->
+<!-- Title: G Pages: 1 -->
+<svg width="62pt" height="116pt"
+ viewBox="0.00 0.00 62.00 116.00" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">
+<g id="graph0" class="graph" transform="scale(1 1) rotate(0) translate(4 112)">"#;
+        assert_eq!(parse_svg_size(svg), Some((62, 116)));
+    }
+
+    #[test]
+    fn parse_svg_size_with_decimals_and_exponents() {
+        let svg = r#"<svg width="8.3e+02pt" height="44.5pt"
+ viewBox="0.00 0.00 830.00 44.50" xmlns="http://www.w3.org/2000/svg">"#;
+        assert_eq!(parse_svg_size(svg), Some((830, 45)));
+    }
+
+    #[test]
+    fn parse_svg_size_in_other_units() {
+        assert_eq!(
+            parse_svg_size(r#"<svg width="100px" height="200px" viewBox="0 0 100 200">"#),
+            Some((75, 150))
+        );
+        assert_eq!(
+            parse_svg_size(r#"<svg width="100" height="200" viewBox="0 0 100 200">"#),
+            Some((75, 150))
+        );
+        assert_eq!(
+            parse_svg_size(r#"<svg width="2in" height="1in">"#),
+            Some((144, 72))
+        );
+    }
+
+    #[test]
+    fn parse_svg_size_failure() {
+        assert_eq!(parse_svg_size(""), None);
+        assert_eq!(parse_svg_size(r#"<svg viewBox="0 0 100 200">"#), None);
+        assert_eq!(parse_svg_size(r#"<svg width="100%" height="100%">"#), None);
+    }
+}